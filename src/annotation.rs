@@ -0,0 +1,321 @@
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnnotationKind{
+    Text,
+    Arrow,
+    /// Shaded rectangle
+    Region
+}
+
+impl AnnotationKind{
+    pub const ALL: [AnnotationKind; 3] = [
+        AnnotationKind::Text,
+        AnnotationKind::Arrow,
+        AnnotationKind::Region
+    ];
+
+    /// Name in scripts and annotation files
+    pub fn keyword(&self) -> &'static str
+    {
+        match self {
+            AnnotationKind::Text => "text",
+            AnnotationKind::Arrow => "arrow",
+            AnnotationKind::Region => "region"
+        }
+    }
+
+    pub fn from_keyword(keyword: &str) -> Option<Self>
+    {
+        Self::ALL.into_iter()
+            .find(|kind| kind.keyword() == keyword)
+    }
+
+    /// Number of clicks needed to place it
+    pub fn points(&self) -> usize
+    {
+        match self {
+            AnnotationKind::Text => 1,
+            AnnotationKind::Arrow | AnnotationKind::Region => 2
+        }
+    }
+}
+
+/// What the y axis of the density plot shows
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PlotView{
+    /// y values are log10
+    pub log_scale: bool,
+    /// neighbour ratios instead of the density
+    pub ratio: bool
+}
+
+/// Something placed on the density plot by the presenter.
+/// The positions are plot coordinates of `view`, i.e., the y values are log10 in log scale
+#[derive(Clone, PartialEq, Debug)]
+pub struct Annotation{
+    pub kind: AnnotationKind,
+    /// Position of the text, tail of the arrow or one corner of the region
+    pub from: [f64; 2],
+    /// Tip of the arrow or the opposite corner of the region. Same as `from` for a text
+    pub to: [f64; 2],
+    /// Optional for arrows and regions
+    pub text: String,
+    /// View in which the annotation was placed
+    pub view: PlotView,
+    /// Placed by a presentation script instead of by hand
+    pub scripted: bool
+}
+
+impl Annotation{
+    /// The annotation in the coordinates of `view`.
+    /// Switching between linear and log scale converts the y values, `None` if that is impossible
+    /// or if the annotation belongs to the other of density and ratio view
+    pub fn in_view(&self, view: PlotView) -> Option<Self>
+    {
+        if self.view.ratio != view.ratio {
+            return None;
+        }
+        let convert = |[x, y]: [f64; 2]| -> Option<[f64; 2]> {
+            match (self.view.log_scale, view.log_scale) {
+                (false, true) if y > 0.0 => Some([x, y.log10()]),
+                (false, true) => None,
+                (true, false) => Some([x, 10_f64.powf(y)]),
+                _ => Some([x, y])
+            }
+        };
+        Some(
+            Self {
+                from: convert(self.from)?,
+                to: convert(self.to)?,
+                view,
+                ..self.clone()
+            }
+        )
+    }
+
+    /// Parses the part after `text =`, `arrow =` or `region =`, i.e.,
+    /// `x y text` for a text and `x1 y1 x2 y2 [text]` otherwise.
+    /// `\#` in the text stands for `#`, see [`strip_comment`]
+    pub fn parse(kind: AnnotationKind, value: &str) -> Result<Self, String>
+    {
+        let mut rest = value.trim();
+        let mut coordinates = Vec::new();
+        for _ in 0..2 * kind.points() {
+            let (number, tail) = rest.split_once(char::is_whitespace)
+                .unwrap_or((rest, ""));
+            let number = number.parse::<f64>()
+                .map_err(|_| format!("expected {} coordinates in `{value}`", 2 * kind.points()))?;
+            coordinates.push(number);
+            rest = tail.trim_start();
+        }
+        if kind == AnnotationKind::Text && rest.is_empty() {
+            return Err("a text annotation needs a text".to_owned());
+        }
+        let from = [coordinates[0], coordinates[1]];
+        let to = if kind.points() == 2 {
+            [coordinates[2], coordinates[3]]
+        } else {
+            from
+        };
+        Ok(
+            Self {
+                kind,
+                from,
+                to,
+                text: rest.replace("\\#", "#"),
+                view: PlotView::default(),
+                scripted: false
+            }
+        )
+    }
+}
+
+/// Same format as in scripts, e.g., `arrow = 0.8 -300 0.9 -350 here`
+impl fmt::Display for Annotation{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {} {}", self.kind.keyword(), self.from[0], self.from[1])?;
+        if self.kind.points() == 2 {
+            write!(f, " {} {}", self.to[0], self.to[1])?;
+        }
+        if !self.text.is_empty() {
+            write!(f, " {}", self.text.replace('#', "\\#"))?;
+        }
+        Ok(())
+    }
+}
+
+/// The part of a line of a script or annotation file before the comment,
+/// i.e., before the first `#` that is not escaped as `\#`
+pub fn strip_comment(line: &str) -> &str
+{
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        if c == '#' && !escaped {
+            return &line[..idx];
+        }
+        escaped = c == '\\';
+    }
+    line
+}
+
+/// Reads a file with one annotation per line, as written by [`save_annotations`].
+/// `log_scale` and `ratio_view` lines set the view of the following annotations,
+/// before the first of them the annotations belong to `view`
+pub fn load_annotations(path: &str, mut view: PlotView) -> Result<Vec<Annotation>, String>
+{
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("{path}: {e}"))?;
+    let mut annotations = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: String| format!("line {}: {message}", idx + 1);
+        let (key, value) = line.split_once('=')
+            .ok_or_else(|| error("expected `kind = value`".to_owned()))?;
+        let (key, value) = (key.trim(), value.trim());
+        let flag = || value.parse::<bool>()
+            .map_err(|_| error(format!("invalid value `{value}` for `{key}`")));
+        match key {
+            "log_scale" => view.log_scale = flag()?,
+            "ratio_view" => view.ratio = flag()?,
+            _ => {
+                let kind = AnnotationKind::from_keyword(key)
+                    .ok_or_else(|| error(format!("unknown annotation `{key}`")))?;
+                let annotation = Annotation::parse(kind, value).map_err(error)?;
+                annotations.push(Annotation { view, ..annotation });
+            }
+        }
+    }
+    Ok(annotations)
+}
+
+/// The lines can also be copied into a scene of a presentation script.
+/// The view is written before the first annotation and whenever it changes
+pub fn save_annotations(path: &str, annotations: &[Annotation]) -> std::io::Result<()>
+{
+    let mut text = String::new();
+    let mut view = None;
+    for annotation in annotations {
+        if view != Some(annotation.view) {
+            view = Some(annotation.view);
+            text.push_str(&format!("log_scale = {}\nratio_view = {}\n", annotation.view.log_scale, annotation.view.ratio));
+        }
+        text.push_str(&format!("{annotation}\n"));
+    }
+    std::fs::write(path, text)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn round_trip(annotation: &Annotation) -> Annotation
+    {
+        let line = annotation.to_string();
+        let (key, value) = strip_comment(&line).split_once('=').unwrap();
+        let kind = AnnotationKind::from_keyword(key.trim()).unwrap();
+        Annotation::parse(kind, value).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display()
+    {
+        let arrow = Annotation::parse(AnnotationKind::Arrow, " 0.8 -300 0.9 -350  the tail ").unwrap();
+        assert_eq!(arrow.from, [0.8, -300.0]);
+        assert_eq!(arrow.to, [0.9, -350.0]);
+        assert_eq!(arrow.text, "the tail");
+        assert_eq!(arrow.to_string(), "arrow = 0.8 -300 0.9 -350 the tail");
+
+        let text = Annotation::parse(AnnotationKind::Text, "0.5 -2.5 here").unwrap();
+        assert_eq!(text.from, text.to);
+        assert_eq!(text.to_string(), "text = 0.5 -2.5 here");
+
+        let region = Annotation::parse(AnnotationKind::Region, "0 1 2 3").unwrap();
+        assert!(region.text.is_empty());
+        assert_eq!(region.to_string(), "region = 0 1 2 3");
+    }
+
+    #[test]
+    fn round_trip_keeps_hash()
+    {
+        for kind in AnnotationKind::ALL {
+            let annotation = Annotation{
+                kind,
+                from: [0.25, -12.5],
+                to: if kind.points() == 2 { [0.75, 3.0] } else { [0.25, -12.5] },
+                text: "bin #3 # twice".to_owned(),
+                view: PlotView::default(),
+                scripted: false
+            };
+            assert_eq!(round_trip(&annotation), annotation);
+        }
+    }
+
+    #[test]
+    fn parse_errors()
+    {
+        assert!(Annotation::parse(AnnotationKind::Text, "0.5 -2.5").is_err());
+        assert!(Annotation::parse(AnnotationKind::Text, "0.5 here").is_err());
+        assert!(Annotation::parse(AnnotationKind::Arrow, "0 1 2").is_err());
+        assert!(Annotation::parse(AnnotationKind::Region, "").is_err());
+        assert!(AnnotationKind::from_keyword("circle").is_none());
+    }
+
+    #[test]
+    fn comments()
+    {
+        assert_eq!(strip_comment("text = 0 0 a # comment"), "text = 0 0 a ");
+        assert_eq!(strip_comment("text = 0 0 \\#1 # comment"), "text = 0 0 \\#1 ");
+        assert_eq!(strip_comment("# only a comment"), "");
+        assert_eq!(strip_comment("no comment"), "no comment");
+    }
+
+    #[test]
+    fn save_and_load()
+    {
+        let log = PlotView { log_scale: true, ratio: false };
+        let annotations = vec![
+            Annotation::parse(AnnotationKind::Text, "0.1 2 peak #1").unwrap(),
+            Annotation { view: log, ..Annotation::parse(AnnotationKind::Region, "0 1 2 3").unwrap() }
+        ];
+        let path = std::env::temp_dir().join(format!("annotations_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        save_annotations(path, &annotations).unwrap();
+        // the view is stored in the file, so the view of the app does not matter
+        let loaded = load_annotations(path, log);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.unwrap(), annotations);
+
+        let error = load_annotations("/this/file/does/not/exist", log).unwrap_err();
+        assert!(error.starts_with("/this/file/does/not/exist"), "{error}");
+    }
+
+    #[test]
+    fn views()
+    {
+        let linear = PlotView::default();
+        let log = PlotView { log_scale: true, ratio: false };
+        let ratio = PlotView { log_scale: true, ratio: true };
+        let arrow = Annotation {
+            view: log,
+            ..Annotation::parse(AnnotationKind::Arrow, "0.5 -2 0.6 1 tail").unwrap()
+        };
+        assert_eq!(arrow.in_view(log), Some(arrow.clone()));
+        assert_eq!(arrow.in_view(ratio), None);
+
+        let converted = arrow.in_view(linear).unwrap();
+        assert_eq!(converted.view, linear);
+        assert_eq!(converted.from, [0.5, 0.01]);
+        assert_eq!(converted.to, [0.6, 10.0]);
+        assert_eq!(converted.text, "tail");
+        let back = converted.in_view(log).unwrap();
+        assert!((back.from[1] + 2.0).abs() < 1e-12 && (back.to[1] - 1.0).abs() < 1e-12);
+
+        // there is no logarithm of negative values
+        let text = Annotation::parse(AnnotationKind::Text, "0.5 -2 below").unwrap();
+        assert_eq!(text.in_view(log), None);
+    }
+}
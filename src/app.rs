@@ -1,28 +1,38 @@
 use egui::{
-    emath::Align, Button, Color32, FontData, FontDefinitions, FontFamily, Layout, Vec2b, Visuals
+    Align2, Button, Color32, FontData, FontDefinitions, FontFamily, Stroke, TextureHandle, TextureOptions, Vec2b
 };
 use egui_plot::*;
-use rand::SeedableRng;
-use sampling::*;
-use std::{time::{Instant, Duration}, thread};
-use sampling::norm_log10_sum_to_1;
-use crate::{CoinSeq, generate_cs};
-use rand::distributions::Uniform;
-use rand::distributions::Distribution;
+use std::time::{Instant, Duration};
+use sampling::{Histogram, norm_log10_sum_to_1};
+use crate::{CoinSeq, generate_cs, WlParams, Flatness, Reduction, RefineSchedule, Handoff, StepInfo, EnsembleMove, ModelKind, Observable};
+use crate::{Recording, Replay, Snapshot, Work, ExportSettings, Exporter, FrameFormat, SvgPlot, HistHistory};
+use crate::Canonical;
+use crate::{Script, Presentation, Setting, Curve, Shortcuts, Action};
+use crate::{Lang, Texts, fill};
+use crate::{PlotLayout, PlotPanel, Arrangement};
+use crate::{Annotation, AnnotationKind, PlotView, load_annotations, save_annotations};
+use crate::{Theme, Styles, Series, marker_symbol};
+use crate::{PrecisionLimit, log10_error, log10_ratios, ratio_errors};
+use crate::{saturation_error, one_over_t_error, saturation_steps};
+
+/// Shortcuts are read from this file at start up, if it exists
+const SHORTCUT_FILE: &str = "shortcuts.txt";
 pub struct SimData{
     c: CoinSeq
 }
 
+/// Result of pressing the single step button
+pub struct SingleStep{
+    wl: StepInfo<EnsembleMove>,
+    entropic: Option<StepInfo<EnsembleMove>>,
+    simple: Option<usize>
+}
+
 #[derive(PartialEq, Eq)]
 pub enum Scale{
     Log,
     Lin
 }
-#[derive(PartialEq)]
-pub enum LightMode{
-    Light,
-    Dark
-}
 
 
 pub struct AppState{
@@ -30,31 +40,73 @@ pub struct AppState{
     pause: bool,
     log_scale: bool,
     speed: f32,
+    model: ModelKind,
     n: usize,
     log_f: Vec<[f64;2]>,
+    /// Run time, Markov steps and error of the Wang-Landau estimate
+    wl_errors: Vec<[f64;3]>,
+    show_error: bool,
     start_time: Option<Instant>,
     pause_time: Option<Instant>,
     pause_duration: Duration,
     log_f_logscale: bool,
     seed: u64,
-    step_size: usize,
     pixel: f32,
     linewidth: f32,
-    threshold: f64,
-    refine_steps: usize,
+    wl_params: WlParams,
+    schedule: RefineSchedule,
+    refine_marks: Vec<f64>,
+    entropic_start: Option<f64>,
+    single_step: Option<SingleStep>,
+    step_all: bool,
+    recording: Recording,
+    replay: Option<Replay>,
+    export_settings: ExportSettings,
+    exporter: Option<Exporter>,
+    export_status: String,
+    svg_path: String,
+    svg_requested: bool,
+    svg_status: String,
+    show_residuals: bool,
+    show_rate: bool,
+    show_reweighting: bool,
+    beta: f64,
+    show_metropolis: bool,
+    show_heatmap: bool,
+    hist_history: HistHistory,
+    heatmap_texture: Option<TextureHandle>,
     hist_scale: Scale,
-    l_mode: LightMode,
-    a_color: Color32,
-    wl_color: Color32,
-    e_color: Color32,
-    s_color: Color32,
+    theme: Theme,
+    styles: Styles,
     show_simp_hist: bool,
-    pairs: bool,
-    f_steps: i32,
-    noise: i32,
+    ratio_view: bool,
+    precision: PrecisionLimit,
+    /// Predicted error of the best estimate and actual error of Wang-Landau
+    precision_errors: Option<(f64, f64)>,
     best: bool,
-    limit_to_1: bool,
-    noise_seed: u64
+    script_path: String,
+    script_status: String,
+    presentation: Option<Presentation>,
+    /// The scene of the presentation changed and still has to be applied
+    scene_changed: bool,
+    /// Run time at which the simulation is paused, set by the presentation
+    pause_at: Option<f64>,
+    shortcuts: Shortcuts,
+    shortcuts_status: String,
+    show_help: bool,
+    lang: Lang,
+    show_side_panel: bool,
+    plot_layout: PlotLayout,
+    show_layout_editor: bool,
+    show_style_editor: bool,
+    annotations: Vec<Annotation>,
+    /// Kind of annotation that is placed by clicking into the density plot
+    annotation_tool: Option<AnnotationKind>,
+    annotation_text: String,
+    /// First click of an arrow or region
+    annotation_start: Option<[f64; 2]>,
+    annotation_path: String,
+    annotation_status: String
 }
 
 impl Default for AppState{
@@ -64,31 +116,67 @@ impl Default for AppState{
             pause: false, 
             log_scale: true, 
             speed: 1.2, 
+            model: ModelKind::Coins,
             n: 1500, 
             log_f: Vec::new(), 
+            wl_errors: Vec::new(),
+            show_error: false,
             start_time: None, 
             log_f_logscale: false,
-            step_size: 1,
             seed: 834628956578,
             pixel: 2.0,
-            linewidth: 1.5,
-            threshold: 0.000001,
+            linewidth: Theme::Light.linewidth(),
+            wl_params: WlParams::default(),
             pause_time: None,
             pause_duration: Duration::new(0, 0),
-            refine_steps: 30000000,
+            schedule: RefineSchedule::default(),
+            refine_marks: Vec::new(),
+            entropic_start: None,
+            single_step: None,
+            step_all: false,
+            recording: Recording::default(),
+            replay: None,
+            export_settings: ExportSettings::default(),
+            exporter: None,
+            export_status: String::new(),
+            svg_path: "density.svg".to_owned(),
+            svg_requested: false,
+            svg_status: String::new(),
+            show_residuals: false,
+            show_rate: false,
+            show_reweighting: false,
+            beta: 0.0,
+            show_metropolis: false,
+            show_heatmap: false,
+            hist_history: HistHistory::default(),
+            heatmap_texture: None,
             hist_scale: Scale::Lin,
-            l_mode: LightMode::Light,
-            a_color: Color32::from_rgb(0x_D8, 0x_1B, 0x_60),
-            s_color: Color32::BLACK,
-            wl_color: Color32::from_rgb(0x_1E, 0x_88, 0x_E5),
-            e_color: Color32::from_rgb(0x_ff, 0x_C1, 0x_07),
+            theme: Theme::Light,
+            styles: Styles::new(&Theme::Light.palette(), Theme::Light.linewidth()),
             show_simp_hist: false,
-            pairs: false,
-            f_steps: 0,
-            noise: 0,
+            ratio_view: false,
+            precision: PrecisionLimit::default(),
+            precision_errors: None,
             best: false,
-            limit_to_1: false,
-            noise_seed: 1238947
+            script_path: "presentation.txt".to_owned(),
+            script_status: String::new(),
+            presentation: None,
+            scene_changed: false,
+            pause_at: None,
+            shortcuts: Shortcuts::default(),
+            shortcuts_status: String::new(),
+            show_help: false,
+            lang: Lang::default(),
+            show_side_panel: true,
+            plot_layout: PlotLayout::default(),
+            show_layout_editor: false,
+            show_style_editor: false,
+            annotations: Vec::new(),
+            annotation_tool: None,
+            annotation_text: String::new(),
+            annotation_start: None,
+            annotation_path: "annotations.txt".to_owned(),
+            annotation_status: String::new()
         }
     }
 }
@@ -104,7 +192,7 @@ impl AppState{
         //if let Some(storage) = cc.storage {
         //    return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
         //}
-        cc.egui_ctx.set_visuals(Visuals::light());
+        cc.egui_ctx.set_visuals(Theme::Light.visuals());
         cc.egui_ctx.set_pixels_per_point(2.0);
         
 
@@ -117,8 +205,124 @@ impl AppState{
 
         cc.egui_ctx.set_fonts(fonts);
         
-        Default::default()
+        let mut state = Self::default();
+        if std::path::Path::new(SHORTCUT_FILE).exists() {
+            load_shortcuts(&mut state.shortcuts, &mut state.shortcuts_status, state.lang.texts());
+        }
+        state
+    }
+
+    /// Performs the actions of the pressed shortcuts.
+    /// Returns true if the simulation has to be restarted
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) -> bool
+    {
+        let mut restart = false;
+        for action in self.shortcuts.pressed(ctx) {
+            match action {
+                Action::Start => restart = true,
+                Action::Pause => {
+                    let value = !self.pause;
+                    set_pause(&mut self.pause, &mut self.pause_time, &mut self.pause_duration, value);
+                },
+                Action::LogScale => self.log_scale = !self.log_scale,
+                Action::RatioView => self.ratio_view = !self.ratio_view,
+                Action::Noise => self.best = !self.best,
+                Action::HistScale => {
+                    self.hist_scale = match self.hist_scale {
+                        Scale::Lin => Scale::Log,
+                        Scale::Log => Scale::Lin
+                    };
+                },
+                Action::ZoomIn | Action::ZoomOut => {
+                    let step = if action == Action::ZoomIn { 0.25 } else { -0.25 };
+                    self.pixel = (self.pixel + step).clamp(1.0, 5.0);
+                    ctx.set_pixels_per_point(self.pixel);
+                },
+                Action::NextScene => {
+                    if let Some(presentation) = self.presentation.as_mut() {
+                        self.scene_changed |= presentation.next_scene();
+                    }
+                },
+                Action::PreviousScene => {
+                    if let Some(presentation) = self.presentation.as_mut() {
+                        self.scene_changed |= presentation.previous_scene();
+                    }
+                },
+                Action::SidePanel => self.show_side_panel = !self.show_side_panel,
+                Action::Fullscreen => {
+                    let fullscreen = ctx.input(|i| i.viewport().fullscreen.unwrap_or(false));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
+                },
+                Action::Help => self.show_help = !self.show_help
+            }
+        }
+        restart
+    }
 
+    /// Applies the scene if it changed.
+    /// Returns true if the scene requests a restart of the simulation
+    fn update_presentation(&mut self) -> bool
+    {
+        if !self.scene_changed {
+            return false;
+        }
+        self.scene_changed = false;
+        self.apply_scene()
+    }
+
+    /// Sets everything as requested by the current scene.
+    /// Returns true if the simulation has to be restarted
+    fn apply_scene(&mut self) -> bool
+    {
+        let Some(presentation) = self.presentation.as_ref() else {
+            return false;
+        };
+        let settings: Vec<_> = presentation.settings().collect();
+        let mut restart = false;
+        let mut pause = None;
+        self.pause_at = None;
+        // the annotations of the script are added again below, the ones placed by hand stay
+        self.annotations.retain(|annotation| !annotation.scripted);
+        self.annotation_start = None;
+        for setting in settings {
+            match setting {
+                Setting::Model(kind) => self.model = kind,
+                Setting::N(n) => self.n = n,
+                Setting::Seed(seed) => self.seed = seed,
+                Setting::Speed(speed) => self.speed = speed,
+                Setting::Beta(beta) => self.beta = beta,
+                Setting::LogScale(log_scale) => self.log_scale = log_scale,
+                Setting::RatioView(ratio_view) => self.ratio_view = ratio_view,
+                Setting::Noise(noise) => self.precision.noise = noise,
+                Setting::Show(curve, visible) => {
+                    let flag = match curve {
+                        Curve::Residuals => &mut self.show_residuals,
+                        Curve::Rate => &mut self.show_rate,
+                        Curve::Heatmap => &mut self.show_heatmap,
+                        Curve::SimpleHist => &mut self.show_simp_hist,
+                        Curve::Metropolis => &mut self.show_metropolis,
+                        Curve::Reweighting => &mut self.show_reweighting,
+                        Curve::Best => &mut self.best,
+                        Curve::Error => &mut self.show_error
+                    };
+                    *flag = visible;
+                },
+                Setting::Pause(p) => pause = Some(p),
+                Setting::PauseAt(time) => self.pause_at = Some(time),
+                Setting::Restart => restart = true,
+                Setting::Annotate(annotation) => {
+                    // the coordinates belong to the view set by the lines before
+                    let view = PlotView { log_scale: self.log_scale, ratio: self.ratio_view };
+                    self.annotations.push(Annotation { view, scripted: true, ..annotation });
+                },
+                Setting::ClearAnnotations => self.annotations.retain(|annotation| !annotation.scripted)
+            }
+        }
+        self.n = self.n.min(self.model.max_n());
+        if let Some(p) = pause {
+            set_pause(&mut self.pause, &mut self.pause_time, &mut self.pause_duration, p);
+        }
+        restart || self.sim.is_none()
     }
 }
 
@@ -134,46 +338,121 @@ impl eframe::App for AppState {
     /// Called each time the UI needs repainting, which may be many times per second.
     /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let shortcut_restart = self.handle_shortcuts(ctx);
+        let scene_restart = self.update_presentation();
         let Self {
             pause,
             sim,
             log_scale,
             speed,
+            model,
             n,
             log_f,
+            wl_errors,
+            show_error,
             start_time,
             log_f_logscale,
             seed,
-            step_size,
             pixel,
             linewidth,
-            threshold,
+            wl_params,
             pause_duration,
             pause_time,
-            refine_steps,
+            schedule,
+            refine_marks,
+            entropic_start,
+            single_step,
+            step_all,
+            recording,
+            replay,
+            export_settings,
+            exporter,
+            export_status,
+            svg_path,
+            svg_requested,
+            svg_status,
+            show_residuals,
+            show_rate,
+            show_reweighting,
+            beta,
+            show_metropolis,
+            show_heatmap,
+            hist_history,
+            heatmap_texture,
             hist_scale,
-            l_mode,
-            a_color,
-            s_color,
-            e_color,
-            wl_color,
+            theme,
+            styles,
             show_simp_hist,
-            pairs,
-            f_steps,
-            noise,
+            ratio_view,
+            precision,
+            precision_errors,
             best,
-            limit_to_1,
-            noise_seed
+            script_path,
+            script_status,
+            presentation,
+            scene_changed,
+            pause_at,
+            shortcuts,
+            shortcuts_status,
+            show_help,
+            lang,
+            show_side_panel,
+            plot_layout,
+            show_layout_editor,
+            show_style_editor,
+            annotations,
+            annotation_tool,
+            annotation_text,
+            annotation_start,
+            annotation_path,
+            annotation_status
         } = self;
+        let t = lang.texts();
         //// Examples of how to create different panels and windows.
         // Pick whichever suits you.
         // Tip: a good default choice is to just keep the `CentralPanel`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
 
+        let screenshot = ctx.input(
+            |i| 
+            {
+                i.events.iter()
+                    .find_map(
+                        |event| 
+                        match event {
+                            egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                            _ => None
+                        }
+                    )
+            }
+        );
+        if let Some(image) = screenshot {
+            let status = exporter.as_mut()
+                .and_then(
+                    |export|
+                    {
+                        match export.write(&image, ctx.pixels_per_point()) {
+                            Err(e) => Some(fill(t.export_failed, &[&e])),
+                            Ok(_) if export.is_finished() => Some(fill(t.exported_frames, &[&export.frames()])),
+                            Ok(_) => None
+                        }
+                    }
+                );
+            if let Some(status) = status {
+                *export_status = status;
+                *exporter = None;
+            }
+        }
+        if exporter.as_ref().is_some_and(|export| export.timed_out()) {
+            *export_status = t.export_timed_out.to_owned();
+            *exporter = None;
+        }
+
+        let mut restart = shortcut_restart || scene_restart;
         egui::SidePanel::left("side_panel")
             .default_width(300.0)
-            .show(ctx, |ui| {
+            .show_animated(ctx, *show_side_panel, |ui| {
 
 
             egui::ScrollArea::both().show(
@@ -181,64 +460,355 @@ impl eframe::App for AppState {
                 |ui|
                 {
 
-                    match l_mode{
-                        LightMode::Dark => {
-                            if ui.add(
-                                 Button::new("☀").frame(false)
-                             ).on_hover_text("Wechsel in den hellen Modus")
-                             .clicked(){
-                                ui.ctx().set_visuals(Visuals::light());
-                                *l_mode = LightMode::Light;
+                    let previous_theme = *theme;
+                    egui::ComboBox::from_label(t.theme)
+                        .selected_text(theme.name(t))
+                        .show_ui(
+                            ui,
+                            |ui|
+                            {
+                                for option in Theme::ALL {
+                                    ui.selectable_value(theme, option, option.name(t));
+                                }
                             }
-                        },
-                        LightMode::Light => {
-                            if ui.add(
-                                 Button::new("🌙").frame(false)
-                             ).on_hover_text("Wechsel in den Dunklen Modus")
-                             .clicked(){
-                                ui.ctx().set_visuals(Visuals::dark());
-                                *l_mode = LightMode::Dark;
+                        );
+                    if *theme != previous_theme {
+                        ui.ctx().set_visuals(theme.visuals());
+                        *styles = Styles::new(&theme.palette(), theme.linewidth());
+                        *linewidth = theme.linewidth();
+                    }
+
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.label(t.language);
+                            for option in [Lang::De, Lang::En]{
+                                if ui.radio_value(lang, option, option.name()).changed(){
+                                    ui.ctx().send_viewport_cmd(
+                                        egui::ViewportCommand::Title(
+                                            option.texts().window_title.to_owned()
+                                        )
+                                    );
+                                }
                             }
                         }
-                    }
+                    );
 
-                    if ui.add(egui::Button::new("Start"))
-                        .on_hover_text("Startet die Simulation.")
+                    if ui.add(egui::Button::new(t.shortcuts))
+                        .on_hover_text(t.shortcuts_hover)
                         .clicked()
                     {
-                        *sim = Some(
-                            SimData { c: generate_cs(*n, *seed, *step_size, *threshold) }
-                        );
-                        *log_f = Vec::new();
-                        *start_time = Some(Instant::now());
-                        *pause_duration = Duration::new(0, 0);
-                        if let Some(ins) = pause_time
+                        *show_help = !*show_help;
+                    }
+
+                    ui.horizontal(
+                        |ui|
                         {
-                            *ins = Instant::now();
+                            if ui.add(Button::new("◀").frame(false))
+                                .on_hover_text(t.hide_side_panel)
+                                .clicked()
+                            {
+                                *show_side_panel = false;
+                            }
+                            let mut fullscreen = ui.ctx().input(|i| i.viewport().fullscreen.unwrap_or(false));
+                            if ui.checkbox(&mut fullscreen, t.fullscreen).changed() {
+                                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Fullscreen(fullscreen));
+                            }
+                            if ui.add(egui::Button::new(t.layout))
+                                .on_hover_text(t.layout_hover)
+                                .clicked()
+                            {
+                                *show_layout_editor = !*show_layout_editor;
+                            }
                         }
+                    );
+
+                    if ui.add(egui::Button::new(t.start))
+                        .on_hover_text(t.start_hover)
+                        .clicked()
+                    {
+                        restart = true;
                     }
                     let btn_text = if *pause{
-                        "Fortfahren"
+                        t.continue_
                     } else{
-                        "Pausieren"
+                        t.pause
                     };
                     if ui.add(egui::Button::new(btn_text))
                         .clicked()
                     {
-                        *pause = !*pause;
-                        if *pause {
-                            *pause_time = Some(Instant::now());
-                        } else {
-                            let dur = pause_time.as_ref().unwrap().elapsed();
-                            *pause_time = None;
-                            *pause_duration += dur;
+                        set_pause(pause, pause_time, pause_duration, !*pause);
+                    }
+
+                    if let Some(sim_data) = sim {
+                        ui.horizontal(
+                            |ui|
+                            {
+                                if ui.add(egui::Button::new(t.single_step))
+                                    .on_hover_text(t.single_step_hover)
+                                    .clicked()
+                                {
+                                    set_pause(pause, pause_time, pause_duration, true);
+                                    let wl = sim_data.c.wl.write().unwrap().wang_landau_step_info();
+                                    let (entropic, simple) = if *step_all {
+                                        (
+                                            sim_data.c.entr.as_mut().map(|entr| entr.entropic_step_info()),
+                                            Some(sim_data.c.simple.lock().sample_once())
+                                        )
+                                    } else {
+                                        (None, None)
+                                    };
+                                    *single_step = Some(SingleStep { wl, entropic, simple });
+                                }
+                                ui.checkbox(step_all, t.step_all)
+                                    .on_hover_text(t.step_all_hover);
+                            }
+                        );
+                    }
+
+                    ui.checkbox(&mut recording.enabled, t.record);
+                    ui.add(egui::Slider::new(&mut recording.interval, 0.05..=10.0).logarithmic(true).text(t.record_interval));
+                    if !recording.is_empty() {
+                        let duration = recording.duration();
+                        let mut go_live = false;
+                        match replay {
+                            None => {
+                                if ui.add(egui::Button::new(t.replay))
+                                    .on_hover_text(t.replay_hover)
+                                    .clicked()
+                                {
+                                    set_pause(pause, pause_time, pause_duration, true);
+                                    *replay = Some(Replay{time: duration, ..Default::default()});
+                                }
+                            },
+                            Some(r) => {
+                                ui.add(egui::Slider::new(&mut r.time, 0.0..=duration).logarithmic(false).text(t.time));
+                                ui.add(egui::Slider::new(&mut r.speed, -10.0..=10.0).logarithmic(false).text(t.replay_speed));
+                                ui.horizontal(
+                                    |ui|
+                                    {
+                                        let text = if r.playing {
+                                            "⏸"
+                                        } else {
+                                            "▶"
+                                        };
+                                        if ui.add(egui::Button::new(text))
+                                            .clicked()
+                                        {
+                                            r.playing = !r.playing;
+                                        }
+                                        if ui.add(egui::Button::new(t.live))
+                                            .clicked()
+                                        {
+                                            go_live = true;
+                                        }
+                                    }
+                                );
+                            }
+                        }
+                        if go_live {
+                            *replay = None;
+                        }
+                    }
+
+                    ui.label(t.export_animation);
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.radio_value(&mut export_settings.format, FrameFormat::Gif, "GIF");
+                            ui.radio_value(&mut export_settings.format, FrameFormat::PngSequence, t.png_sequence);
+                        }
+                    );
+                    ui.text_edit_singleline(&mut export_settings.path)
+                        .on_hover_text(t.export_path_hover);
+                    ui.add(egui::Slider::new(&mut export_settings.fps, 1..=60).logarithmic(false).text(t.fps));
+                    ui.add(egui::Slider::new(&mut export_settings.frames, 1..=10000).logarithmic(true).text(t.frames));
+                    ui.add(egui::Slider::new(&mut export_settings.steps_per_frame, 1..=100000000).logarithmic(true).text(t.steps_per_frame));
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.add(egui::DragValue::new(&mut export_settings.width).clamp_range(16..=7680));
+                            ui.label("×");
+                            ui.add(egui::DragValue::new(&mut export_settings.height).clamp_range(16..=4320));
+                            ui.label(t.frame_size);
+                        }
+                    );
+                    match exporter {
+                        None => {
+                            if ui.add(egui::Button::new(t.export_frames))
+                                .on_hover_text(t.export_frames_hover)
+                                .clicked()
+                            {
+                                match Exporter::new(export_settings) {
+                                    Ok(export) => {
+                                        *exporter = Some(export);
+                                        *export_status = String::new();
+                                        restart = true;
+                                    },
+                                    Err(e) => *export_status = fill(t.export_failed, &[&e])
+                                }
+                            }
+                        },
+                        Some(export) => {
+                            ui.label(fill(t.frame_of, &[&export.frame(), &export.frames()]));
+                            ui.label(t.export_keep_visible);
+                            if ui.add(egui::Button::new(t.abort))
+                                .clicked()
+                            {
+                                *exporter = None;
+                                *export_status = t.export_aborted.to_owned();
+                            }
+                        }
+                    }
+                    if !export_status.is_empty() {
+                        ui.label(export_status.as_str());
+                    }
+
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.text_edit_singleline(svg_path);
+                            if ui.add_enabled(sim.is_some(), egui::Button::new(t.export_svg))
+                                .on_hover_text(t.export_svg_hover)
+                                .clicked()
+                            {
+                                *svg_requested = true;
+                            }
+                        }
+                    );
+                    if !svg_status.is_empty() {
+                        ui.label(svg_status.as_str());
+                    }
+
+                    ui.label(t.presentation);
+                    match presentation {
+                        None => {
+                            ui.horizontal(
+                                |ui|
+                                {
+                                    ui.text_edit_singleline(script_path);
+                                    if ui.add(egui::Button::new(t.load))
+                                        .on_hover_text(t.load_presentation_hover)
+                                        .clicked()
+                                    {
+                                        match Script::load(script_path) {
+                                            Ok(script) => {
+                                                *presentation = Some(Presentation::new(script));
+                                                *scene_changed = true;
+                                                *script_status = String::new();
+                                            },
+                                            Err(e) => *script_status = fill(t.loading_failed, &[&e])
+                                        }
+                                    }
+                                }
+                            );
+                        },
+                        Some(p) => {
+                            ui.horizontal(
+                                |ui|
+                                {
+                                    if ui.add(egui::Button::new("◀")).clicked() && p.previous_scene() {
+                                        *scene_changed = true;
+                                    }
+                                    ui.label(fill(t.scene_of, &[&(p.index() + 1), &p.scene_count()]));
+                                    if ui.add(egui::Button::new("▶")).clicked() && p.next_scene() {
+                                        *scene_changed = true;
+                                    }
+                                }
+                            );
+                            if ui.add(egui::Button::new(t.end_presentation))
+                                .clicked()
+                            {
+                                *presentation = None;
+                                *pause_at = None;
+                            }
+                        }
+                    }
+                    if !script_status.is_empty() {
+                        ui.label(script_status.as_str());
+                    }
+
+                    ui.label(t.annotations);
+                    let previous_tool = *annotation_tool;
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.radio_value(annotation_tool, None, t.annotation_off);
+                            for kind in AnnotationKind::ALL {
+                                let name = match kind {
+                                    AnnotationKind::Text => t.annotation_text_kind,
+                                    AnnotationKind::Arrow => t.annotation_arrow,
+                                    AnnotationKind::Region => t.annotation_region
+                                };
+                                ui.radio_value(annotation_tool, Some(kind), name);
+                            }
+                        }
+                    );
+                    if *annotation_tool != previous_tool {
+                        *annotation_start = None;
+                    }
+                    if annotation_tool.is_some() {
+                        ui.text_edit_singleline(annotation_text)
+                            .on_hover_text(t.annotation_text_hover);
+                        ui.label(
+                            if annotation_start.is_some() {
+                                t.annotation_click_second
+                            } else {
+                                t.annotation_click
+                            }
+                        );
+                    }
+                    let mut remove = None;
+                    for (idx, annotation) in annotations.iter().enumerate() {
+                        ui.horizontal(
+                            |ui|
+                            {
+                                if ui.add(Button::new("🗑").frame(false))
+                                    .on_hover_text(t.remove)
+                                    .clicked()
+                                {
+                                    remove = Some(idx);
+                                }
+                                ui.label(annotation.to_string());
+                            }
+                        );
+                    }
+                    if let Some(idx) = remove {
+                        annotations.remove(idx);
+                    }
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.text_edit_singleline(annotation_path);
+                            if ui.add(egui::Button::new(t.save)).clicked() {
+                                *annotation_status = match save_annotations(annotation_path, annotations) {
+                                    Ok(_) => fill(t.saved, &[annotation_path]),
+                                    Err(e) => fill(t.saving_failed, &[&e])
+                                };
+                            }
+                            if ui.add(egui::Button::new(t.load)).clicked() {
+                                let view = PlotView { log_scale: *log_scale, ratio: *ratio_view };
+                                *annotation_status = match load_annotations(annotation_path, view) {
+                                    Ok(loaded) => {
+                                        *annotations = loaded;
+                                        fill(t.loaded, &[annotation_path])
+                                    },
+                                    Err(e) => fill(t.loading_failed, &[&e])
+                                };
+                            }
                         }
+                    );
+                    if ui.add_enabled(!annotations.is_empty(), egui::Button::new(t.remove_all)).clicked() {
+                        annotations.clear();
+                    }
+                    if !annotation_status.is_empty() {
+                        ui.label(annotation_status.as_str());
                     }
 
                     let btn_text = if *log_scale {
-                        "Switch to linear Scale"
+                        t.to_linear
                     } else {
-                        "Switch to logscale"
+                        t.to_log
                     };
 
                     if ui.add(egui::Button::new(btn_text))
@@ -246,15 +816,48 @@ impl eframe::App for AppState {
                     {
                         *log_scale = !*log_scale;
                     }
-                    ui.add(egui::Slider::new(speed, 0.0..=10.0).logarithmic(false).text("Speed"));
-                    ui.add(egui::Slider::new(n, 10..=10000).logarithmic(true).text("N"));
-                    ui.add(egui::Slider::new(seed, 0..=u64::MAX).logarithmic(true).text("Seed"));
-                    ui.add(egui::Slider::new(step_size, 1..=30).logarithmic(false).text("step size"));
+                    ui.add(egui::Slider::new(speed, 0.0..=10.0).logarithmic(false).text(t.speed));
+                    ui.horizontal(
+                        |ui|
+                        {
+                            ui.radio_value(model, ModelKind::Coins, t.coins);
+                            if ui.radio(matches!(model, ModelKind::Dice{..}), t.dice).clicked() {
+                                *model = ModelKind::Dice{faces: 6};
+                            }
+                            if ui.radio(matches!(model, ModelKind::Walk{..}), t.random_walk).clicked() {
+                                *model = ModelKind::Walk{observable: Observable::End};
+                            }
+                        }
+                    );
+                    match model {
+                        ModelKind::Dice{faces} => {
+                            ui.add(egui::Slider::new(faces, 2..=20).logarithmic(false).text(t.faces))
+                                .on_hover_text(t.faces_hover);
+                        },
+                        ModelKind::Walk{observable} => {
+                            ui.horizontal(
+                                |ui|
+                                {
+                                    ui.radio_value(observable, Observable::End, t.walk_end)
+                                        .on_hover_text(t.walk_end_hover);
+                                    ui.radio_value(observable, Observable::Max, t.walk_max)
+                                        .on_hover_text(t.walk_max_hover);
+                                    ui.radio_value(observable, Observable::Area, t.walk_area)
+                                        .on_hover_text(t.walk_area_hover);
+                                }
+                            );
+                        },
+                        ModelKind::Coins => ()
+                    }
+                    *n = (*n).min(model.max_n());
+                    ui.add(egui::Slider::new(n, 10..=model.max_n()).logarithmic(true).text("N"));
+                    ui.add(egui::Slider::new(seed, 0..=u64::MAX).logarithmic(true).text(t.seed));
+                    ui.add(egui::Slider::new(&mut wl_params.step_size, 1..=30).logarithmic(false).text(t.step_size));
 
                     let btn_text = if *log_f_logscale {
-                        "to_log_f"
+                        t.to_log_f
                     } else {
-                        "to log_log_f"
+                        t.to_log_log_f
                     };
 
                     if ui.add(egui::Button::new(btn_text))
@@ -262,50 +865,210 @@ impl eframe::App for AppState {
                     {
                         *log_f_logscale = !*log_f_logscale;
                     }
+                    ui.checkbox(show_error, t.error_plot)
+                        .on_hover_text(t.error_plot_hover);
 
-                    ui.add(egui::Slider::new(pixel, 1.0..=5.0).logarithmic(false).text("Zoom"));
-                    if ui.add(egui::Button::new("Rescale"))
+                    ui.add(egui::Slider::new(pixel, 1.0..=5.0).logarithmic(false).text(t.zoom));
+                    if ui.add(egui::Button::new(t.rescale))
                         .clicked()
                     {
                         ctx.set_pixels_per_point(*pixel);
                     }
 
-                    ui.add(egui::Slider::new(linewidth, 0.0..=10.0).logarithmic(false).text("line"));
-                    ui.add(egui::Slider::new(threshold, 0.00000000001..=0.001).logarithmic(true).text("threshold"));
-                    ui.add(egui::Slider::new(refine_steps, 100000..=10000000000).logarithmic(true).text("E refine"));
-                    ui.radio_value(hist_scale, Scale::Lin, "Hist Lin");
-                    ui.radio_value(hist_scale, Scale::Log, "Hist Log");
+                    if ui.add(egui::Slider::new(linewidth, 0.0..=10.0).logarithmic(false).text(t.line))
+                        .changed()
+                    {
+                        styles.set_linewidth(*linewidth);
+                    }
+                    ui.add(egui::Slider::new(&mut wl_params.threshold, 0.00000000001..=0.001).logarithmic(true).text(t.threshold));
+                    ui.add(egui::Slider::new(&mut wl_params.log_f_start, 0.001..=10.0).logarithmic(true).text(t.initial_log_f));
+                    ui.add(egui::Slider::new(&mut wl_params.check_every, 1..=1000000).logarithmic(true).text(t.check_every));
+
+                    ui.label(t.flatness);
+                    ui.horizontal(
+                        |ui|
+                        {
+                            if ui.radio(matches!(wl_params.flatness, Flatness::AllVisited), t.all_visited)
+                                .clicked()
+                            {
+                                wl_params.flatness = Flatness::AllVisited;
+                            }
+                            if ui.radio(matches!(wl_params.flatness, Flatness::MinOverMean(_)), t.min_over_mean)
+                                .clicked()
+                            {
+                                wl_params.flatness = Flatness::MinOverMean(0.8);
+                            }
+                        }
+                    );
+                    if let Flatness::MinOverMean(ratio) = &mut wl_params.flatness {
+                        ui.add(egui::Slider::new(ratio, 0.01..=0.99).logarithmic(false).text(t.min_over_mean));
+                    }
+
+                    ui.label(t.log_f_reduction);
+                    ui.horizontal(
+                        |ui|
+                        {
+                            let factor = match wl_params.reduction {
+                                Reduction::Divide(factor) | Reduction::OneOverT(factor) => factor
+                            };
+                            if ui.radio(matches!(wl_params.reduction, Reduction::Divide(_)), "log_f / x")
+                                .clicked()
+                            {
+                                wl_params.reduction = Reduction::Divide(factor);
+                            }
+                            if ui.radio(matches!(wl_params.reduction, Reduction::OneOverT(_)), "1/t")
+                                .clicked()
+                            {
+                                wl_params.reduction = Reduction::OneOverT(factor);
+                            }
+                        }
+                    );
+                    match &mut wl_params.reduction {
+                        Reduction::Divide(factor) | Reduction::OneOverT(factor) => {
+                            ui.add(egui::Slider::new(factor, 1.1..=10.0).logarithmic(true).text("x"));
+                        }
+                    }
+                    if let Some(sim_data) = sim {
+                        let wl = sim_data.c.wl.read().unwrap();
+                        let mode = if wl.is_one_over_t() {
+                            "1/t"
+                        } else {
+                            t.flat_histogram
+                        };
+                        ui.label(fill(t.log_f_mode, &[&format!("{:e}", wl.log_f()), &mode]));
+                        ui.label(fill(t.wl_steps, &[&wl.step_counter()]));
+                    }
+                    ui.label(t.start_entropic);
+                    ui.horizontal_wrapped(
+                        |ui|
+                        {
+                            ui.radio_value(&mut schedule.handoff, Handoff::Immediately, t.immediately);
+                            if ui.radio(matches!(schedule.handoff, Handoff::LogF(_)), t.at_log_f)
+                                .clicked()
+                            {
+                                schedule.handoff = Handoff::LogF(0.001);
+                            }
+                            ui.radio_value(&mut schedule.handoff, Handoff::Finished, t.wl_finished);
+                            ui.radio_value(&mut schedule.handoff, Handoff::Manual, t.manual);
+                        }
+                    );
+                    if let Handoff::LogF(log_f) = &mut schedule.handoff {
+                        ui.add(egui::Slider::new(log_f, 0.00000000001..=10.0).logarithmic(true).text(t.entropic_start_log_f));
+                    }
+                    if let Some(sim_data) = sim {
+                        if ui.add(egui::Button::new(t.branch_entropic))
+                            .on_hover_text(t.branch_entropic_hover)
+                            .clicked()
+                        {
+                            sim_data.c.branch_entropic(*schedule);
+                            let ellased = start_time.as_ref().unwrap().elapsed() - *pause_duration;
+                            *refine_marks = Vec::new();
+                            *entropic_start = Some(ellased.as_secs_f64());
+                        }
+                    }
+                    ui.add(egui::Slider::new(&mut schedule.every, 100000..=10000000000).logarithmic(true).text(t.refine_every));
+                    ui.add(egui::Slider::new(&mut schedule.rounds, 0..=100).logarithmic(false).text(t.refine_rounds));
+                    ui.radio_value(hist_scale, Scale::Lin, t.hist_lin);
+                    ui.radio_value(hist_scale, Scale::Log, t.hist_log);
 
-                    ui.color_edit_button_srgba(a_color);
-                    ui.color_edit_button_srgba(s_color);
-                    ui.color_edit_button_srgba(e_color);
-                    ui.color_edit_button_srgba(wl_color);
+                    if ui.add(egui::Button::new(t.curve_styles))
+                        .on_hover_text(t.curve_styles_hover)
+                        .clicked()
+                    {
+                        *show_style_editor = !*show_style_editor;
+                    }
 
-                    ui.checkbox(show_simp_hist, "Simp Hist");
+                    ui.checkbox(show_simp_hist, t.simple_hist);
+                    ui.checkbox(show_residuals, t.residuals);
+                    ui.checkbox(show_rate, t.rate_function)
+                        .on_hover_text(t.rate_function_hover);
+                    ui.checkbox(show_metropolis, t.metropolis)
+                        .on_hover_text(t.metropolis_hover);
+                    if *show_metropolis {
+                        ui.add(egui::Slider::new(beta, -1.0..=1.0).logarithmic(false).text("β"))
+                            .on_hover_text(t.bias_hover);
+                    }
+                    ui.checkbox(show_reweighting, t.reweighting)
+                        .on_hover_text(t.reweighting_hover);
+                    ui.checkbox(show_heatmap, t.hist_heatmap);
 
-                    let text = if *pairs {
-                        "Normal"
+                    let text = if *ratio_view {
+                        t.density_view
                     } else {
-                        "Pairs"
+                        t.ratio_view
                     };
 
                     if ui.add(egui::Button::new(text))
+                        .on_hover_text(t.ratio_view_hover)
                         .clicked()
                     {
-                        *pairs = !*pairs;
+                        *ratio_view = !*ratio_view;
                     }
-                    ui.checkbox(best, "Noise");
+                    ui.checkbox(best, t.noise);
                     if *best{
-                        ui.add(egui::Slider::new(f_steps, 0..=7).logarithmic(false).text("Best PR"));
-                        ui.add(egui::Slider::new(noise, 0..=30).logarithmic(false).text("noise"));
-                        ui.add(egui::Slider::new(noise_seed, 0..=2000012).logarithmic(false).text("noise seed"));
-                        ui.checkbox(limit_to_1, "limit to 1");
+                        ui.add(egui::Slider::new(&mut precision.f_steps, 0..=7).logarithmic(false).text(t.best_pr))
+                            .on_hover_text(t.best_pr_hover);
+                        ui.add(egui::Slider::new(&mut precision.noise, 0..=30).logarithmic(false).text(t.noise_amount));
+                        ui.add(egui::Slider::new(&mut precision.noise_seed, 0..=2000012).logarithmic(false).text(t.noise_seed));
+                        ui.checkbox(&mut precision.limit_to_1, t.limit_to_1);
+                        if let Some((predicted, actual)) = precision_errors {
+                            ui.label(fill(t.precision_errors, &[&format!("{predicted:.2e}"), &format!("{actual:.2e}")]));
+                        }
                     }
                 }
             );
             
         });
 
+        if !*show_side_panel {
+            egui::Area::new(egui::Id::new("show_side_panel"))
+                .anchor(egui::Align2::LEFT_TOP, [4.0, 4.0])
+                .show(
+                    ctx,
+                    |ui|
+                    {
+                        if ui.add(Button::new("▶").frame(false))
+                            .on_hover_text(t.show_side_panel)
+                            .clicked()
+                        {
+                            *show_side_panel = true;
+                        }
+                    }
+                );
+        }
+
+        if let Some(p) = presentation {
+            egui::TopBottomPanel::bottom("caption")
+                .show(
+                    ctx,
+                    |ui|
+                    {
+                        ui.vertical_centered(
+                            |ui| ui.heading(p.scene().caption.as_str())
+                        );
+                    }
+                );
+        }
+
+        if restart {
+            *sim = Some(
+                SimData { c: generate_cs(*model, *n, *seed, *wl_params, *schedule) }
+            );
+            *log_f = Vec::new();
+            *wl_errors = Vec::new();
+            *refine_marks = Vec::new();
+            *entropic_start = None;
+            recording.clear();
+            hist_history.clear();
+            *replay = None;
+            *start_time = Some(Instant::now());
+            *pause_duration = Duration::new(0, 0);
+            if let Some(ins) = pause_time
+            {
+                *ins = Instant::now();
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // The central panel the region left after adding TopPanel's and SidePanel's
 
@@ -317,523 +1080,926 @@ impl eframe::App for AppState {
             //));
 
             if let Some(sim_data) = sim{
+                let x_label = sim_data.c.kind.x_label(t);
+                let y_label = if *ratio_view {
+                    t.ratio_axis
+                } else {
+                    sim_data.c.kind.y_label(t)
+                };
+                sim_data.c.set_metropolis((*show_metropolis || *show_reweighting).then_some(*beta));
+                sim_data.c.poll_exact();
+
+                let work = match exporter {
+                    Some(export) if !export.is_waiting() && !export.is_finished() => {
+                        Some(Work::Steps(export.steps_per_frame))
+                    },
+                    Some(_) => None,
+                    None if !*pause => Some(Work::Millis(30.0 * *speed)),
+                    None => None
+                };
 
-                let time = Instant::now();
-                if !*pause
-                {
-                    let s = *speed;
-                    let wl = sim_data.c.wl.clone();
-                    let t = thread::spawn(
-                        move || {
-                            wl.write().unwrap().wang_landau_while_acc(
-                                |ensemble, step, old_energy| {
-                                    ensemble.update_head_count(step, old_energy)
-                                }, 
-                                |_| {(time.elapsed().as_millis() as f32) < (30.0_f32 * s)}
-                            );
-                        }
-                    );
+                let run_time = match exporter {
+                    Some(export) => export.time(),
+                    None => (start_time.as_ref().unwrap().elapsed() - *pause_duration).as_secs_f64()
+                };
 
-                    let simp = sim_data.c.simple.clone();
+                if pause_at.is_some_and(|time| run_time >= time) {
+                    *pause_at = None;
+                    set_pause(pause, pause_time, pause_duration, true);
+                }
 
-                    let t2 = thread::spawn(
-                        move ||
-                        {
-                            simp.lock().sample_while(|| (time.elapsed().as_millis() as f32) < (30.0_f32 * s))
-                        }
-                    );
+                if let Some(work) = work {
+                    sim_data.c.step(work);
 
-                    sim_data.c.entr.entropic_sampling_while_acc(
-                        |ensemble, step, old_energy| {
-                            ensemble.update_head_count(step, old_energy)
-                        }, 
-                        |_| {}, 
-                        |_| {(time.elapsed().as_millis() as f32) < (30.0_f32 * s)}
-                    );
+                    if let Some(entr) = &sim_data.c.entr {
+                        while refine_marks.len() < entr.refinements() {
+                            refine_marks.push(run_time);
+                        }
+                    }
+                    if sim_data.c.try_start_entropic(*schedule) {
+                        *entropic_start = Some(run_time);
+                    }
 
-                    if sim_data.c.entr.step_counter() > *refine_steps{
-                        sim_data.c.entr.refine_estimate();
+                    let wl = sim_data.c.wl.read().unwrap();
+                    if !wl.is_finished() {
+                        log_f.push([run_time, wl.log_f()]);
+                        let mut density = wl.log_density_base10();
+                        norm_log10_sum_to_1(&mut density);
+                        if let Some(error) = log10_error(&density, &sim_data.c.log_prob_true) {
+                            wl_errors.push([run_time, wl.step_counter() as f64, error]);
+                        }
                     }
+                    drop(wl);
 
-                    t.join().unwrap();
-                    t2.join().unwrap();
-                }
+                    recording.record(&sim_data.c, run_time);
+                    hist_history.record(run_time, sim_data.c.wl.read().unwrap().hist().hist());
 
-                if !*pause && ! sim_data.c.wl.read().unwrap().is_finished() {
-                    let current_log_f: f64 = sim_data.c.wl.read().unwrap().log_f();
-                    let ellased = start_time.as_ref().unwrap().elapsed() - *pause_duration;
-                    log_f.push([ellased.as_secs_f64(), current_log_f]);
+                    if let Some(export) = exporter {
+                        export.request(ui.max_rect());
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot);
+                    }
                 }
-                
 
-                let layout = Layout{
-                    main_dir: egui::Direction::LeftToRight,
-                    main_wrap: false,
-                    main_align: Align::Center,
-                    main_justify: true,
-                    cross_align: Align::Min,
-                    cross_justify: true
+                if let Some(r) = replay.as_mut() {
+                    r.advance(ctx.input(|i| i.unstable_dt) as f64, recording.duration());
+                }
+                let live;
+                let snapshot = match replay.as_ref().and_then(|r| recording.at(r.time)) {
+                    Some(snap) => snap,
+                    None => {
+                        live = Snapshot::from_sim(&sim_data.c, run_time);
+                        &live
+                    }
                 };
+                
 
-               ui.with_layout(
-                        layout, 
+               ui.scope(
                         |ui|{
-                            let max_width = ui.available_width();
-                            let mut density = sim_data.c.wl.read().unwrap().log_density_base10();
+                            let rects = plot_layout.rects(ui.available_rect_before_wrap(), ui.spacing().item_spacing.x);
+                            let rect_of = |panel| rects.iter().find(|(p, _)| *p == panel).map(|(_, rect)| *rect);
+                            let density = &snapshot.wl_density;
                             let len = density.len();
-                            let mut true_density = sim_data.c.log_prob_true.clone();
-                            
-                            let mut e_data: Vec<_> = sim_data.c.entr.log_density_estimate()
-                                .iter().map(|val|  *val * std::f64::consts::LOG10_E)
-                                .collect();
-                            norm_log10_sum_to_1(&mut e_data);
-                            norm_log10_sum_to_1(&mut density);
-                            norm_log10_sum_to_1(&mut true_density);
-
-                            let total = 2.0_f64.powi(-*f_steps);
-
-                            let num = 2_u64.pow(*f_steps as u32) * 8;
-
-                            
-
-                            let list: Vec<_> = (0..=num)
-                                .map(
-                                    |v|
-                                    {
-                                        let v = v as f64 * total;
-                                        (-v).exp()
-                                    }
-                                ).collect();
-
-                            let mut rng = rand_pcg::Pcg64::seed_from_u64(*noise_seed);
-                            let closes = |val: f64| {
-                                let r = list[0];
-                                let mut diff = (val-r).abs();
-                                let mut idx = 0;
-                                for (v, i) in list[1..].iter().zip(1..)
-                                {
-                                    let n_diff = (val-*v).abs();
-                                    if n_diff < diff {
-                                        diff = n_diff;
-                                        idx = i;
-                                    }
-                                }
-                                idx
-                            };
+                            let true_density = &sim_data.c.log_prob_true;
+                            let e_data = &snapshot.e_density;
 
-                            let mut best_estimate: Vec<_> = true_density.windows(2)
-                                    .map(
-                                        |arr| 
-                                        closes( 10_f64.powf(-(arr[0] - arr[1]).abs()))
-                                    )
-                                    .collect();
-
-                            let uni = Uniform::new(0.0, 1.0);
-                            let other = Uniform::new_inclusive(0, *noise);
-                            for i in 1..(best_estimate.len()-1)
-                            {
-                                let p = (0.5 - i as f64 / (best_estimate.len()-1) as f64).abs() * 2.0 + 0.075;
-                                if uni.sample(&mut rng) < (p*p)  {
-                                    let v = other.sample(&mut rng);
-                                    best_estimate[i-1] += v;
-                                    best_estimate[i] -= v;
-                                }
-                                
-                                
-                            }
-
-                            fn with_limit(v: f64) -> f64
-                            {
-                                v.min(0.0).exp()
-                            }
-
-                            fn without_limit(v: f64) -> f64
+                            let residual = |estimate: &[f64]| -> Vec<[f64;2]>
                             {
-                                v.exp()
-                            }
-
-                            let fun = if *limit_to_1{
-                                with_limit
-                            } else {
-                                without_limit
-                            };
-
-                            let mut best_estimate: Vec<[f64;2]> = best_estimate
-                            .iter()
-                            .enumerate()
-                            .map(
-                                |(i, arr)| 
-                                [i as f64 / len as f64, 
-                                    fun(-*arr as f64 * total)
-                                ] 
-                            )
-                            .collect();
-
-                            if !*pairs{
-                                let start = -10.0;
-                                let mut other = vec![start];
-
-                                best_estimate.iter()
-                                    .for_each(
-                                        |v|
-                                        {
-                                            let last = other.last().unwrap();
-                                            
-                                            if v[0] < 0.5 {
-                                                let r = -v[1].log10()+last;
-                                                other.push(r);
-                                            } else {
-                                                let r = v[1].log10()+last;
-                                                other.push(r);
-                                            }
-                                        }
-                                    );
-                                norm_log10_sum_to_1(&mut other);
-                                best_estimate = 
-                                    other.iter()
-                                    .enumerate()
-                                    .map(|(i, v)| [i as f64 / len as f64, *v] )
-                                .collect();
-                                
-                                if !*log_scale {
-                                    best_estimate.iter_mut()
-                                        .for_each(|v| v[1] = 10_f64.powf(v[1]));
-                                }
-                                    
-                            } 
-
-                            let simp_data = if *log_scale{
-                                sim_data.c.simple.lock().get_prob_log10()
-                            } else {
-                                sim_data.c.simple.lock().get_prob()
-                            };
-                            if !*log_scale
-                            {
-                                density.iter_mut()
-                                    .for_each(|val| *val = 10.0f64.powf(*val));
-                                true_density.iter_mut()
-                                    .for_each(|val| *val = 10.0f64.powf(*val));
-                                e_data.iter_mut()
-                                    .for_each(|val| *val = 10.0f64.powf(*val));
-                            }
-                            
-
-                            let density: Vec<_> = if *pairs {
-                                density.windows(2)
-                                    .map(|arr| 10_f64.powf(-(arr[0] - arr[1]).abs()) )
-                                    .collect()  
-                            } else {
-                                density
-                            };
-
-                            let wl_density: Vec<_> = 
-                                density.into_iter()
+                                let errors = if *ratio_view {
+                                    ratio_errors(estimate, true_density)
+                                } else {
+                                    estimate.iter()
+                                        .zip(true_density.iter())
+                                        .map(|(est, exact)| est - exact)
+                                        .collect()
+                                };
+                                errors.into_iter()
                                     .enumerate()
-                                .map(
-                                    |(idx, den)|
-                                    {
-                                        let x = idx as f64 / len as f64;
-                                        let y = den;
-                                        [x,y]
-                                    }
-                                ).collect();
-    
-                            let true_density: Vec<_> = if *pairs {
-                                true_density.windows(2)
-                                    .map(|arr| 10_f64.powf(-(arr[0] - arr[1]).abs()) )
-                                    .collect()  
-                            } else {
-                                true_density
+                                    .map(|(idx, error)| [idx as f64 / len as f64, error])
+                                    .collect()
                             };
-
-
-
-                            let true_density: Vec<_> = 
-                                true_density.into_iter()
-                                    .enumerate()
+                            let wl_residual = residual(density);
+                            let e_residual = residual(e_data);
+                            let s_residual = residual(&snapshot.s_prob_log10());
+
+                            let wl_rate = rate_points(density, sim_data.c.n);
+                            let e_rate = rate_points(e_data, sim_data.c.n);
+                            let true_rate = rate_points(true_density, sim_data.c.n);
+                            let exact_rate: Option<Vec<[f64;2]>> = (0..=200)
                                 .map(
-                                    |(idx, den)|
+                                    |i|
                                     {
-                                        let x = idx as f64 / len as f64;
-                                        let y = den;
-                                        [x,y]
+                                        let x = i as f64 / 200.0;
+                                        sim_data.c.kind.rate_function(x).map(|y| [x, y])
                                     }
                                 ).collect();
 
-                            let e_data: Vec<_> = if *pairs {
-                                e_data.windows(2)
-                                    .map(|arr| 10_f64.powf(-(arr[0] - arr[1]).abs()) )
-                                    .collect()  
+                            *precision_errors = if *best {
+                                precision.predicted_error(true_density)
+                                    .zip(log10_error(density, true_density))
                             } else {
-                                e_data
+                                None
                             };
 
-                            let e_density: Vec<_> = 
-                                e_data.into_iter()
+                            // what the density plot shows of a log10 density:
+                            // the density itself or the ratios of neighbouring bins
+                            let view = |log10_density: &[f64]| -> Vec<[f64;2]>
+                            {
+                                let values = if *ratio_view {
+                                    log10_ratios(log10_density)
+                                } else {
+                                    log10_density.to_vec()
+                                };
+                                values.into_iter()
                                     .enumerate()
-                                .map(
-                                    |(idx, den)|
-                                    {
-                                        let x = idx as f64 / len as f64;
-                                        let y = den;
-                                        [x,y]
-                                    }
-                                ).collect();
-
-                            let simp_data: Vec<_> = if *pairs {
-                                simp_data.windows(2)
-                                    .map(|arr| 10_f64.powf(-(arr[0] - arr[1]).abs()) )
-                                    .collect()  
-                            } else {
-                                simp_data
+                                    .map(
+                                        |(idx, val)|
+                                        {
+                                            let x = idx as f64 / len as f64;
+                                            let y = if *log_scale {
+                                                val
+                                            } else {
+                                                10_f64.powf(val)
+                                            };
+                                            [x, y]
+                                        }
+                                    ).collect()
                             };
-
-                            let s_density: Vec<_> = 
-                                simp_data.into_iter()
-                                    .enumerate()
+                            let wl_density = view(density);
+                            let true_density = view(true_density);
+                            let best_estimate = view(&precision.log10_density(&sim_data.c.log_prob_true));
+                            let e_density = view(e_data);
+                            let s_density = view(&snapshot.s_prob_log10());
+                            let m_density = view(&snapshot.m_prob_log10());
+                            let exact_ratio: Option<Vec<[f64;2]>> = (0..len.saturating_sub(1))
                                 .map(
-                                    |(idx, den)|
+                                    |k|
                                     {
-                                        let x = idx as f64 / len as f64;
-                                        let y = den;
-                                        [x,y]
+                                        let x = k as f64 / len as f64;
+                                        sim_data.c.kind.neighbour_ratio(sim_data.c.n, k)
+                                            .map(|ratio| [x, if *log_scale { ratio.log10() } else { ratio }])
                                     }
                                 ).collect();
 
+                            let curves = [
+                                (Series::Analytic, &true_density, true),
+                                (Series::Wl, &wl_density, true),
+                                (Series::Best, &best_estimate, *best),
+                                (Series::Entropic, &e_density, true),
+                                (Series::Simple, &s_density, true),
+                                (Series::Metropolis, &m_density, *show_metropolis)
+                            ];
+
+                            if *svg_requested {
+                                *svg_requested = false;
+                                let series = curves.iter()
+                                    .filter(|(series, _, shown)| *shown && styles[*series].visible)
+                                    .map(|(series, data, _)| styles[*series].svg(series.name(t), data))
+                                    .collect();
+                                let svg = SvgPlot{
+                                    x_label,
+                                    y_label,
+                                    log_y: *log_scale,
+                                    series
+                                };
+                                *svg_status = match svg.save(svg_path) {
+                                    Ok(_) => fill(t.saved, &[svg_path]),
+                                    Err(e) => fill(t.svg_failed, &[&e])
+                                };
+                            }
 
-                            ui.vertical(
-                                |ui|
-                                {
-                                    let legend = Legend::default().position(Corner::RightBottom)
-                                        .background_alpha(0.5);
-                                    let hight = ui.available_height();
-                                    let mut p = Plot::new("plot_average_etc")
-                                    .include_x(0.0)
-                                    .x_axis_formatter(|g, _, _| format!("{}", g.value));
-
-                                    if *log_scale && !*pairs{
-                                        p = p.y_axis_formatter(
-                                            |g, _,_| 
+                            if let Some(rect) = rect_of(PlotPanel::Density) {
+                                ui.allocate_ui_at_rect(
+                                    rect,
+                                    |ui|
+                                    {
+                                        let legend = Legend::default().position(Corner::RightBottom)
+                                            .background_alpha(0.5);
+                                        let annotation_color = ui.visuals().strong_text_color();
+                                        let hight = ui.available_height();
+                                        let show_errors = *show_residuals || *ratio_view;
+                                        let density_height = match (show_errors, *show_rate) {
+                                            (false, false) => hight - 25.0,
+                                            (true, true) => (hight - 25.0) * 0.45,
+                                            _ => (hight - 25.0) * 0.65
+                                        };
+                                        let mut p = Plot::new("plot_average_etc")
+                                        .include_x(0.0)
+                                        .x_axis_formatter(|g, _, _| format!("{}", g.value));
+
+                                        if *log_scale{
+                                            p = p.y_axis_formatter(
+                                                |g, _,_| 
+                                                {
+                                                    let s = format!("{}", g.value);
+                                                    let ex: String = s.chars().map(exchange).collect();
+                                                    format!("10{ex}")
+                                                }
+                                            );
+                                        }
+                                    
+                                        p.x_grid_spacer(
+                                            |_|
                                             {
-                                                let s = format!("{}", g.value);
-                                                let ex: String = s.chars().map(exchange).collect();
-                                                format!("10{ex}")
+                                                vec![
+                                                    GridMark { value: 0.0, step_size: 0.5 },
+                                                    GridMark { value: 0.25, step_size: 0.5 },
+                                                    GridMark { value: 0.5, step_size: 0.5 },
+                                                    GridMark { value: 0.75, step_size: 0.5 },
+                                                    GridMark { value: 1.0, step_size: 0.5 },
+                                                ]
                                             }
-                                        );
-                                    }
-                                    
-                                    p.x_grid_spacer(
-                                        |_|
-                                        {
-                                            vec![
-                                                GridMark { value: 0.0, step_size: 0.5 },
-                                                GridMark { value: 0.25, step_size: 0.5 },
-                                                GridMark { value: 0.5, step_size: 0.5 },
-                                                GridMark { value: 0.75, step_size: 0.5 },
-                                                GridMark { value: 1.0, step_size: 0.5 },
-                                            ]
-                                        }
-                                    )
-                                    .legend(legend)
-                                    .height(hight - 25.0)
-                                    .width(max_width * 0.5)
-                                    .y_axis_label("Probability of heads rate")
-                                    .x_axis_label("Heads rate")
-                                    .show(
-                                        ui, 
-                                        |plot_ui|
-                                        {
+                                        )
+                                        .legend(legend)
+                                        .height(density_height)
+                                            .y_axis_label(y_label)
+                                        .x_axis_label(x_label)
+                                        .show(
+                                            ui, 
+                                            |plot_ui|
+                                            {
                                             
-                                            let true_line = Line::new(true_density).name("analytic Results")
-                                                .width(*linewidth*2.0)
-                                                .color(*a_color);
-
-                                            plot_ui.line(true_line);
-                                            if *pairs {
-                                                let wl_points = Points::new(wl_density)
-                                                .name("WL Results")
-                                                .radius(*linewidth*0.8)
-                                                .color(*wl_color);
-                                                plot_ui.points(wl_points);
-
+                                                if let Some(exact_ratio) = exact_ratio.filter(|_| *ratio_view) {
+                                                    let analytic = Line::new(exact_ratio).name("(n-k) / (k+1)")
+                                                        .width(*linewidth)
+                                                        .style(LineStyle::dashed_loose())
+                                                        .color(styles[Series::Best].color);
+                                                    plot_ui.line(analytic);
+                                                }
+                                                for (series, data, shown) in curves {
+                                                    if shown {
+                                                        styles[series].show(plot_ui, series.name(t), data);
+                                                    }
+                                                }
 
+                                                // annotations of the other view are hidden
+                                                let view = PlotView { log_scale: *log_scale, ratio: *ratio_view };
+                                                for annotation in annotations.iter().filter_map(|annotation| annotation.in_view(view)) {
+                                                    show_annotation(plot_ui, &annotation, annotation_color, *linewidth);
+                                                }
+                                                if let Some(start) = annotation_start {
+                                                    plot_ui.points(
+                                                        Points::new(vec![*start])
+                                                            .radius(*linewidth*2.0)
+                                                            .color(annotation_color)
+                                                    );
+                                                }
+                                                let click = plot_ui.pointer_coordinate()
+                                                    .filter(|_| plot_ui.response().clicked());
+                                                if let (Some(kind), Some(pos)) = (*annotation_tool, click) {
+                                                    let pos = [round_coordinate(pos.x), round_coordinate(pos.y)];
+                                                    match annotation_start.take() {
+                                                        None if kind.points() == 2 => *annotation_start = Some(pos),
+                                                        _ if kind == AnnotationKind::Text && annotation_text.is_empty() => (),
+                                                        start => {
+                                                            annotations.push(
+                                                                Annotation{
+                                                                    kind,
+                                                                    from: start.unwrap_or(pos),
+                                                                    to: pos,
+                                                                    text: annotation_text.clone(),
+                                                                    view,
+                                                                    scripted: false
+                                                                }
+                                                            );
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        );
 
+                                        if show_errors {
+                                            let hight = if *show_rate {
+                                                ui.available_height() * 0.5
                                             } else {
-                                                let wl_line = Line::new(wl_density).name("WL Results")
-                                                .width(*linewidth)
-                                                .color(*wl_color);
-                                                plot_ui.line(wl_line);
-                                            }
-                                            if *best{
-                                                if *log_scale{
-                                                    let p = Points::new(best_estimate)
-                                                    .name("best")
-                                                    .radius(*linewidth*0.7)
-                                                        .color(Color32::DARK_GRAY);
-                                                    plot_ui.points(p);
-                                                }else {
-                                                    let p = Line::new(best_estimate)
-                                                    .name("best")
-                                                    .width(*linewidth*0.9)
-                                                        .color(Color32::DARK_GRAY);
-                                                    plot_ui.line(p);
+                                                ui.available_height()
+                                            };
+                                            Plot::new("plot_residuals")
+                                            .include_x(0.0)
+                                            .include_x(1.0)
+                                            .include_y(0.0)
+                                            .legend(Legend::default().position(Corner::RightBottom).background_alpha(0.5))
+                                            .height(hight - 25.0)
+                                                .y_axis_label(if *ratio_view { t.ratio_error_axis } else { t.residual_axis })
+                                            .x_axis_label(x_label)
+                                            .show(
+                                                ui,
+                                                |plot_ui|
+                                                {
+                                                    plot_ui.hline(
+                                                        HLine::new(0.0)
+                                                            .width(*linewidth)
+                                                            .color(styles[Series::Analytic].color)
+                                                    );
+                                                    styles[Series::Wl].show(plot_ui, t.wl_results, &wl_residual);
+                                                    styles[Series::Entropic].show(plot_ui, t.entropic_results, &e_residual);
+                                                    styles[Series::Simple].show(plot_ui, t.simple_results, &s_residual);
                                                 }
-                                                
-                                            }
+                                            );
+                                        }
 
-                                            
-                                            
-                                            let ent_line = Line::new(e_density).name("Entropic Results")
-                                                .width(*linewidth)
-                                                .color(*e_color);
-                                            let s_points = Points::new(s_density)
-                                                .name("Simple Results")
-                                                .radius(*linewidth*0.9)
-                                                .shape(MarkerShape::Cross)
-                                                .color(*s_color);
-                                            
-                                            
-                                            
-                                            plot_ui.line(ent_line);
-                                            plot_ui.points(s_points);
-                                            
-                                            
+                                        if *show_rate {
+                                            let hight = ui.available_height();
+                                            Plot::new("plot_rate_function")
+                                            .include_x(0.0)
+                                            .include_x(1.0)
+                                            .include_y(0.0)
+                                            .legend(Legend::default().position(Corner::RightBottom).background_alpha(0.5))
+                                            .height(hight - 25.0)
+                                                .y_axis_label("-ln P / n")
+                                            .x_axis_label(x_label)
+                                            .show(
+                                                ui,
+                                                |plot_ui|
+                                                {
+                                                    if let Some(exact_rate) = exact_rate {
+                                                        let binary = Line::new(exact_rate).name("ln 2 + x ln x + (1-x) ln(1-x)")
+                                                            .width(*linewidth)
+                                                            .style(LineStyle::dashed_loose())
+                                                            .color(styles[Series::Best].color);
+                                                        plot_ui.line(binary);
+                                                    }
+                                                    styles[Series::Analytic].show(plot_ui, t.analytic_results, &true_rate);
+                                                    styles[Series::Wl].show(plot_ui, t.wl_results, &wl_rate);
+                                                    styles[Series::Entropic].show(plot_ui, t.entropic_results, &e_rate);
+                                                }
+                                            );
                                         }
-                                    );
-                                }
-                            );
-                            ui.vertical(
-                                |ui|
-                                {
-                                    let name = if *log_f_logscale{
-                                        "log10(logE(f))"
-                                    } else {
-                                        "logE(f)"
-                                    };
+                                    }
+                                );
+                            }
+                            if let Some(rect) = rect_of(PlotPanel::LogF) {
+                                ui.allocate_ui_at_rect(
+                                    rect,
+                                    |ui|
+                                    {
+                                        let name = if *log_f_logscale{
+                                            "log10(logE(f))"
+                                        } else {
+                                            "logE(f)"
+                                        };
 
                                     
 
-                                    let hight = ui.available_height();
-                                    Plot::new("plot_log_f")
-                                    .include_x(0.0)
-                                    .include_y(0.0)
-                                    .auto_bounds(Vec2b::new(true, true))
-                                    .legend(Legend::default())
-                                    .height((hight - 25.0)*0.5)
-                                    .y_axis_label(name)
-                                    .x_axis_label("Run time in seconds")
-                                    .show(
-                                        ui, 
-                                        |plot_ui|
-                                        {
-                                            let mut tmp_log_f = log_f.clone();
-                                            if *log_f_logscale
+                                        let hight = ui.available_height();
+                                        let log_f_height = if *show_error {
+                                            (hight - 25.0) * 0.5
+                                        } else {
+                                            hight - 25.0
+                                        };
+                                        Plot::new("plot_log_f")
+                                        .include_x(0.0)
+                                        .include_y(0.0)
+                                        .auto_bounds(Vec2b::new(true, true))
+                                        .legend(Legend::default())
+                                        .height(log_f_height)
+                                        .y_axis_label(name)
+                                        .x_axis_label(t.run_time_axis)
+                                        .show(
+                                            ui, 
+                                            |plot_ui|
                                             {
-                                                tmp_log_f.iter_mut()
-                                                    .for_each(|[_, val]| *val = val.log10());
-                                            }
+                                                let mut tmp_log_f: Vec<_> = log_f.iter()
+                                                    .filter(|[time, _]| *time <= snapshot.time)
+                                                    .copied()
+                                                    .collect();
+                                                if *log_f_logscale
+                                                {
+                                                    tmp_log_f.iter_mut()
+                                                        .for_each(|[_, val]| *val = val.log10());
+                                                }
                                             
-                                            let log_f_line = Line::new(tmp_log_f).name(name)
-                                                .width(*linewidth)
-                                                .color(*wl_color);
+                                                let log_f_line = Line::new(tmp_log_f).name(name)
+                                                    .width(*linewidth)
+                                                    .color(styles[Series::Wl].color);
                                             
     
-                                            plot_ui.line(log_f_line);
+                                                plot_ui.line(log_f_line);
+
+                                                if let Some(start) = entropic_start.filter(|start| *start <= snapshot.time) {
+                                                    let v_line = VLine::new(start)
+                                                        .name(t.entropic_start)
+                                                        .width(*linewidth)
+                                                        .style(LineStyle::dashed_loose())
+                                                        .color(styles[Series::Entropic].color);
+                                                    plot_ui.vline(v_line);
+                                                }
+
+                                                for mark in refine_marks.iter().filter(|mark| **mark <= snapshot.time)
+                                                {
+                                                    let v_line = VLine::new(*mark)
+                                                        .name(t.entropic_refinement)
+                                                        .width(*linewidth)
+                                                        .color(styles[Series::Entropic].color);
+                                                    plot_ui.vline(v_line);
+                                                }
                                             
-                                        }
-                                    );
-                                    let mut hist: Vec<_> = sim_data.c.wl.read().unwrap().hist().bin_hits_iter()
-                                        .map(|(bin, hits)| [bin as f64 / len as f64, hits as f64])
-                                        .collect();
-                                    let mut ent_hist: Vec<_> = sim_data.c.entr.hist().bin_hits_iter()
-                                        .map(|(bin, hits)| [bin as f64 / len as f64, hits as f64])
-                                        .collect();
-
-                                    let mut s_hist: Vec<_> = sim_data.c.simple.lock().hist.bin_hits_iter()
-                                        .map(|(bin, hits)| [bin as f64 / len as f64, hits as f64])
-                                        .collect();
-
-                                    if matches!(*hist_scale, Scale::Log) {
-                                        hist.iter_mut()
-                                            .for_each(
-                                                |[_, val]|
+                                            }
+                                        );
+
+                                        if *show_error {
+                                            // with log10 on both axes the 1/t scaling is a straight line
+                                            let errors: Vec<[f64;2]> = wl_errors.iter()
+                                                .filter(|[time, _, _]| *time <= snapshot.time)
+                                                .map(|[_, steps, error]| [steps.log10(), error.log10()])
+                                                .collect();
+                                            let params = *sim_data.c.wl.read().unwrap().params();
+                                            let threshold = params.threshold;
+                                            // the limit only exists for classic Wang-Landau,
+                                            // where it is compared with the 1/t algorithm
+                                            let classic = matches!(params.reduction, Reduction::Divide(_));
+                                            // 1/t reaches the limit of classic Wang-Landau here
+                                            let crossing = saturation_steps(len, threshold);
+                                            let first = len.max(1) as f64;
+                                            let last = errors.last()
+                                                .map_or(first * 10.0, |[steps, _]| 10_f64.powf(*steps))
+                                                .max(if classic { crossing } else { first })
+                                                .max(first * 10.0);
+                                            let one_over_t: Vec<[f64;2]> = (0..=100)
+                                                .map(
+                                                    |i|
+                                                    {
+                                                        let steps = first * (last / first).powf(i as f64 / 100.0);
+                                                        [steps.log10(), one_over_t_error(len, steps).log10()]
+                                                    }
+                                                ).collect();
+
+                                            let hight = ui.available_height();
+                                            Plot::new("plot_error")
+                                            .auto_bounds(Vec2b::new(true, true))
+                                            .legend(Legend::default().position(Corner::LeftBottom).background_alpha(0.5))
+                                            .height(hight - 25.0)
+                                            .y_axis_label(t.error_axis)
+                                            .x_axis_label(t.steps_axis)
+                                            .show(
+                                                ui,
+                                                |plot_ui|
                                                 {
-                                                    if *val < 1.0 {
-                                                        *val = f64::NAN;   
-                                                    } else {
-                                                        *val = val.log10();
+                                                    if classic {
+                                                        let limit = HLine::new(saturation_error(threshold).log10())
+                                                            .name(t.classic_limit)
+                                                            .width(*linewidth)
+                                                            .style(LineStyle::dashed_loose())
+                                                            .color(styles[Series::Best].color);
+                                                        plot_ui.hline(limit);
+                                                        let crossing = VLine::new(crossing.log10())
+                                                            .name(t.limit_reached)
+                                                            .width(*linewidth)
+                                                            .style(LineStyle::dashed_loose())
+                                                            .color(styles[Series::Analytic].color);
+                                                        plot_ui.vline(crossing);
                                                     }
-                                                    
+                                                    let scaling = Line::new(one_over_t)
+                                                        .name(t.one_over_t_scaling)
+                                                        .width(*linewidth)
+                                                        .style(LineStyle::dotted_loose())
+                                                        .color(styles[Series::Best].color);
+                                                    plot_ui.line(scaling);
+                                                    let error_line = Line::new(errors)
+                                                        .name(t.wl_results)
+                                                        .width(*linewidth)
+                                                        .color(styles[Series::Wl].color);
+                                                    plot_ui.line(error_line);
                                                 }
                                             );
-                                        ent_hist.iter_mut()
-                                            .for_each(
-                                                |[_, val]|
-                                                {
-                                                    if *val < 1.0 {
-                                                        *val = f64::NAN;   
-                                                    } else {
-                                                        *val = val.log10();
+                                        }
+                                    }
+                                );
+                            }
+                            if let Some(rect) = rect_of(PlotPanel::Histogram) {
+                                ui.allocate_ui_at_rect(
+                                    rect,
+                                    |ui|
+                                    {
+                                        let mut hist: Vec<_> = snapshot.wl_hist.iter()
+                                            .enumerate()
+                                            .map(|(bin, hits)| [bin as f64 / len as f64, *hits as f64])
+                                            .collect();
+                                        let mut ent_hist: Vec<_> = snapshot.e_hist.iter()
+                                            .enumerate()
+                                            .map(|(bin, hits)| [bin as f64 / len as f64, *hits as f64])
+                                            .collect();
+
+                                        let mut s_hist: Vec<_> = snapshot.s_hist.iter()
+                                            .enumerate()
+                                            .map(|(bin, hits)| [bin as f64 / len as f64, *hits as f64])
+                                            .collect();
+
+                                        let mut m_hist: Vec<_> = snapshot.m_hist.iter()
+                                            .enumerate()
+                                            .map(|(bin, hits)| [bin as f64 / len as f64, *hits as f64])
+                                            .collect();
+
+                                        if matches!(*hist_scale, Scale::Log) {
+                                            hist.iter_mut()
+                                                .for_each(
+                                                    |[_, val]|
+                                                    {
+                                                        if *val < 1.0 {
+                                                            *val = f64::NAN;   
+                                                        } else {
+                                                            *val = val.log10();
+                                                        }
+                                                    
+                                                    }
+                                                );
+                                            ent_hist.iter_mut()
+                                                .for_each(
+                                                    |[_, val]|
+                                                    {
+                                                        if *val < 1.0 {
+                                                            *val = f64::NAN;   
+                                                        } else {
+                                                            *val = val.log10();
+                                                        }
+                                                    }
+                                                );
+                                            s_hist.iter_mut()
+                                                .for_each(
+                                                    |[_, val]|
+                                                    {
+                                                        if *val < 1.0 {
+                                                            *val = f64::NAN;   
+                                                        } else {
+                                                            *val = val.log10();
+                                                        }
+                                                    }
+                                                );
+                                            m_hist.iter_mut()
+                                                .for_each(
+                                                    |[_, val]|
+                                                    {
+                                                        if *val < 1.0 {
+                                                            *val = f64::NAN;   
+                                                        } else {
+                                                            *val = val.log10();
+                                                        }
                                                     }
+                                                );
+                                        
+                                        }
+
+                                        let hight = ui.available_height();
+                                        let hist_height = if *show_heatmap {
+                                            (hight - 25.0) * 0.5
+                                        } else {
+                                            hight - 25.0
+                                        };
+                                        Plot::new("plot_histogram")
+                                        .include_x(0.0)
+                                        .include_y(0.0)
+                                        .auto_bounds(Vec2b::new(false, true))
+                                        .legend(Legend::default())
+                                        .height(hist_height)
+                                        .x_axis_label(t.histogram)
+                                        .y_axis_label(t.hits)
+                                        .show(
+                                            ui, 
+                                            |plot_ui|
+                                            {
+                                            
+                                                let histogram = Line::new(hist).name(t.wl_histogram)
+                                                    .width(*linewidth)
+                                                    .color(styles[Series::Wl].color);
+                                            
+                                                let ent_line = Line::new(ent_hist).name(t.entropic_histogram)
+                                                    .width(*linewidth)
+                                                    .color(styles[Series::Entropic].color);
+
+                                            
+    
+                                                plot_ui.line(histogram);
+                                                plot_ui.line(ent_line);
+
+                                                if *show_simp_hist{
+                                                    let s_line = Line::new(s_hist).name(t.simple_histogram)
+                                                        .width(*linewidth)
+                                                        .color(styles[Series::Simple].color);
+                                                    plot_ui.line(s_line);
                                                 }
-                                            );
-                                        s_hist.iter_mut()
-                                            .for_each(
-                                                |[_, val]|
+
+                                                if *show_metropolis {
+                                                    let m_line = Line::new(m_hist).name(t.metropolis_histogram)
+                                                        .width(*linewidth)
+                                                        .color(styles[Series::Metropolis].color);
+                                                    plot_ui.line(m_line);
+                                                }
+                                            
+                                            }
+                                        );
+
+                                        if *show_heatmap {
+                                            let range = hist_history.time_range(snapshot.time);
+                                            let image = hist_history.to_image(snapshot.time, matches!(*hist_scale, Scale::Log));
+                                            if let Some(image) = image {
+                                                match heatmap_texture {
+                                                    Some(texture) => texture.set(image, TextureOptions::NEAREST),
+                                                    None => {
+                                                        *heatmap_texture = Some(
+                                                            ctx.load_texture("hist_heatmap", image, TextureOptions::NEAREST)
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                            let hight = ui.available_height();
+                                            Plot::new("plot_hist_heatmap")
+                                            .include_x(0.0)
+                                            .include_y(0.0)
+                                            .include_y(1.0)
+                                            .height(hight - 25.0)
+                                            .x_axis_label(t.run_time_axis)
+                                            .y_axis_label(x_label)
+                                            .show(
+                                                ui,
+                                                |plot_ui|
                                                 {
-                                                    if *val < 1.0 {
-                                                        *val = f64::NAN;   
-                                                    } else {
-                                                        *val = val.log10();
+                                                    if let (Some((start, end)), Some(texture)) = (range, heatmap_texture.as_ref()) {
+                                                        let heatmap = PlotImage::new(
+                                                            texture,
+                                                            PlotPoint::new((start + end) * 0.5, 0.5),
+                                                            [(end - start) as f32, 1.0]
+                                                        ).name(t.wl_histogram);
+                                                        plot_ui.image(heatmap);
                                                     }
                                                 }
                                             );
-                                        
+                                        }
                                     }
+                                );
+                            }
+                            ctx.request_repaint();
+                        }
+                    );
+
+                if *show_reweighting {
+                    egui::Window::new(t.reweighting)
+                        .open(show_reweighting)
+                        .show(
+                            ctx,
+                            |ui|
+                            {
+                                ui.add(egui::Slider::new(beta, -1.0..=1.0).logarithmic(false).text("β"))
+                                    .on_hover_text(t.beta_hover);
+                                let reweighted = [
+                                    (t.analytic_results, Canonical::reweight(&sim_data.c.log_prob_true, *beta), styles[Series::Analytic].color),
+                                    (t.wl_results, Canonical::reweight(&snapshot.wl_density, *beta), styles[Series::Wl].color),
+                                    (t.entropic_results, Canonical::reweight(&snapshot.e_density, *beta), styles[Series::Entropic].color)
+                                ];
+                                let direct = Canonical::from_hist(&snapshot.m_hist);
+
+                                egui::Grid::new("reweighting_grid")
+                                    .num_columns(3)
+                                    .striped(true)
+                                    .show(
+                                        ui,
+                                        |ui|
+                                        {
+                                            ui.label("");
+                                            ui.label("⟨E⟩");
+                                            ui.label("Var(E)");
+                                            ui.end_row();
+                                            let rows = reweighted.iter()
+                                                .map(|(name, canonical, _)| (*name, canonical.as_ref()))
+                                                .chain(std::iter::once((t.metropolis, direct.as_ref())));
+                                            for (name, canonical) in rows {
+                                                ui.label(name);
+                                                match canonical {
+                                                    Some(c) => {
+                                                        ui.label(format!("{:.3}", c.mean));
+                                                        ui.label(format!("{:.3}", c.variance));
+                                                    },
+                                                    None => {
+                                                        ui.label("-");
+                                                        ui.label("-");
+                                                    }
+                                                }
+                                                ui.end_row();
+                                            }
+                                        }
+                                    );
+                                if let Some(chain) = &sim_data.c.metropolis {
+                                    let chain = chain.lock();
+                                    ui.label(
+                                        fill(
+                                            t.metropolis_info,
+                                            &[
+                                                &chain.step_counter(), 
+                                                &format!("{:.3}", chain.acceptance_rate())
+                                            ]
+                                        )
+                                    );
+                                }
 
-                                    let hight = ui.available_height();
-                                    Plot::new("plot_histogram")
+                                let len = sim_data.c.log_prob_true.len().max(snapshot.wl_density.len());
+                                let to_points = |prob: &[f64]| -> Vec<[f64;2]>
+                                {
+                                    prob.iter()
+                                        .enumerate()
+                                        .map(|(idx, p)| [idx as f64 / len as f64, *p])
+                                        .collect()
+                                };
+                                Plot::new("plot_reweighting")
                                     .include_x(0.0)
+                                    .include_x(1.0)
                                     .include_y(0.0)
-                                    .auto_bounds(Vec2b::new(false, true))
-                                    .legend(Legend::default())
-                                    .height(hight - 25.0)
-                                    .x_axis_label("histogram")
-                                    .y_axis_label("#hits")
+                                    .legend(Legend::default().position(Corner::RightTop).background_alpha(0.5))
+                                    .height(300.0)
+                                    .width(450.0)
+                                    .y_axis_label("P(E)")
+                                    .x_axis_label(x_label)
                                     .show(
-                                        ui, 
+                                        ui,
                                         |plot_ui|
                                         {
-                                            
-                                            let histogram = Line::new(hist).name("Wang Landau Histogram")
-                                                .width(*linewidth)
-                                                .color(*wl_color);
-                                            
-                                            let ent_line = Line::new(ent_hist).name("Entropic Histogram")
-                                                .width(*linewidth)
-                                                .color(*e_color);
-
-                                            
-    
-                                            plot_ui.line(histogram);
-                                            plot_ui.line(ent_line);
-
-                                            if *show_simp_hist{
-                                                let s_line = Line::new(s_hist).name("Simple Histogram")
-                                                    .width(*linewidth)
-                                                    .color(*s_color);
-                                                plot_ui.line(s_line);
+                                            for (name, canonical, color) in reweighted.iter() {
+                                                if let Some(c) = canonical {
+                                                    let line = Line::new(to_points(&c.prob)).name(*name)
+                                                        .width(*linewidth)
+                                                        .color(*color);
+                                                    plot_ui.line(line);
+                                                }
+                                            }
+                                            if let Some(c) = &direct {
+                                                let points = Points::new(to_points(&c.prob))
+                                                    .name(t.metropolis)
+                                                    .radius(*linewidth*0.9)
+                                                    .shape(MarkerShape::Cross)
+                                                    .color(styles[Series::Metropolis].color);
+                                                plot_ui.points(points);
                                             }
-                                            
                                         }
                                     );
-                                    ctx.request_repaint();
+                            }
+                        );
+                }
+            }
+        });
+
+        if *show_style_editor {
+            egui::Window::new(t.curve_styles)
+                .open(show_style_editor)
+                .show(
+                    ctx,
+                    |ui|
+                    {
+                        egui::Grid::new("style_grid")
+                            .num_columns(5)
+                            .striped(true)
+                            .show(
+                                ui,
+                                |ui|
+                                {
+                                    for series in Series::ALL {
+                                        let style = &mut styles[series];
+                                        ui.checkbox(&mut style.visible, series.name(t));
+                                        ui.color_edit_button_srgba(&mut style.color);
+                                        ui.add(
+                                            egui::DragValue::new(&mut style.width)
+                                                .speed(0.05)
+                                                .clamp_range(0.0..=10.0)
+                                        ).on_hover_text(t.width_hover);
+                                        ui.horizontal(
+                                            |ui|
+                                            {
+                                                ui.radio_value(&mut style.points, false, t.draw_line);
+                                                ui.radio_value(&mut style.points, true, t.draw_points);
+                                            }
+                                        );
+                                        ui.add_enabled_ui(
+                                            style.points,
+                                            |ui|
+                                            {
+                                                egui::ComboBox::from_id_source(("marker", series as usize))
+                                                    .selected_text(marker_symbol(style.marker))
+                                                    .width(40.0)
+                                                    .show_ui(
+                                                        ui,
+                                                        |ui|
+                                                        {
+                                                            for shape in MarkerShape::all() {
+                                                                ui.selectable_value(&mut style.marker, shape, marker_symbol(shape));
+                                                            }
+                                                        }
+                                                    );
+                                            }
+                                        );
+                                        ui.end_row();
+                                    }
                                 }
                             );
+                        if ui.add(egui::Button::new(t.reset_styles)).clicked() {
+                            *styles = Styles::new(&theme.palette(), theme.linewidth());
                         }
-                    );
+                    }
+                );
+        }
+
+        if *show_layout_editor {
+            egui::Window::new(t.layout)
+                .open(show_layout_editor)
+                .show(
+                    ctx,
+                    |ui|
+                    {
+                        let mut move_up = None;
+                        egui::Grid::new("layout_grid")
+                            .num_columns(2)
+                            .show(
+                                ui,
+                                |ui|
+                                {
+                                    for (idx, (panel, visible)) in plot_layout.panels.iter_mut().enumerate() {
+                                        let name = match panel {
+                                            PlotPanel::Density => t.density_plot,
+                                            PlotPanel::LogF => t.log_f_plot,
+                                            PlotPanel::Histogram => t.histogram_plot
+                                        };
+                                        ui.checkbox(visible, name);
+                                        if idx > 0 && ui.add(Button::new("⏶").frame(false))
+                                            .on_hover_text(t.move_up)
+                                            .clicked()
+                                        {
+                                            move_up = Some(idx);
+                                        }
+                                        ui.end_row();
+                                    }
+                                }
+                            );
+                        if let Some(idx) = move_up {
+                            plot_layout.move_up(idx);
+                        }
+                        ui.separator();
+                        ui.radio_value(&mut plot_layout.arrangement, Arrangement::Split, t.arrangement_split);
+                        ui.radio_value(&mut plot_layout.arrangement, Arrangement::Columns, t.arrangement_columns);
+                        ui.radio_value(&mut plot_layout.arrangement, Arrangement::Rows, t.arrangement_rows);
+                        ui.add_enabled(
+                            plot_layout.arrangement == Arrangement::Split,
+                            egui::Slider::new(&mut plot_layout.split, 0.2..=0.8).text(t.split_share)
+                        );
+                    }
+                );
+        }
+
+        if *show_help {
+            let mut reload = false;
+            egui::Window::new(t.shortcuts)
+                .open(show_help)
+                .show(
+                    ctx,
+                    |ui|
+                    {
+                        egui::Grid::new("shortcut_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(
+                                ui,
+                                |ui|
+                                {
+                                    for action in Action::ALL {
+                                        ui.label(action.description(t));
+                                        let keys: Vec<_> = shortcuts.of(action)
+                                            .map(|shortcut| ctx.format_shortcut(shortcut))
+                                            .collect();
+                                        ui.label(keys.join(", "));
+                                        ui.end_row();
+                                    }
+                                }
+                            );
+                        ui.label(fill(t.configured_in, &[&SHORTCUT_FILE]));
+                        if ui.add(egui::Button::new(t.reload)).clicked() {
+                            reload = true;
+                        }
+                        if !shortcuts_status.is_empty() {
+                            ui.label(shortcuts_status.as_str());
+                        }
+                    }
+                );
+            if reload {
+                load_shortcuts(shortcuts, shortcuts_status, t);
             }
-        });
+        }
+
+        if let Some(step) = single_step {
+            let mut open = true;
+            egui::Window::new(t.single_step)
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    ui.heading("Wang Landau");
+                    step_info_ui(ui, "wl_step", &step.wl, t);
+                    if let Some(info) = &step.entropic {
+                        ui.separator();
+                        ui.heading(t.entropic);
+                        step_info_ui(ui, "entropic_step", info, t);
+                    }
+                    if let Some(energy) = step.simple {
+                        ui.separator();
+                        ui.heading(t.simple);
+                        ui.label(fill(t.independent_sample, &[&energy]));
+                    }
+                });
+            if !open {
+                *single_step = None;
+            }
+        }
 
         if false {
             egui::Window::new("Window").show(ctx, |ui| {
@@ -847,8 +2013,136 @@ impl eframe::App for AppState {
     }
 }
 
+fn load_shortcuts(shortcuts: &mut Shortcuts, status: &mut String, t: &Texts)
+{
+    match Shortcuts::load(SHORTCUT_FILE) {
+        Ok(loaded) => {
+            *shortcuts = loaded;
+            *status = fill(t.loaded, &[&SHORTCUT_FILE]);
+        },
+        Err(e) => *status = fill(t.loading_failed, &[&e])
+    }
+}
+
+fn set_pause(pause: &mut bool, pause_time: &mut Option<Instant>, pause_duration: &mut Duration, value: bool)
+{
+    if *pause == value {
+        return;
+    }
+    *pause = value;
+    if value {
+        *pause_time = Some(Instant::now());
+    } else if let Some(time) = pause_time.take() {
+        *pause_duration += time.elapsed();
+    }
+}
+
+/// Empirical rate function -ln P / n over the rate, `log10_prob` is normalized
+fn rate_points(log10_prob: &[f64], n: usize) -> Vec<[f64;2]>
+{
+    let last = log10_prob.len().saturating_sub(1).max(1) as f64;
+    log10_prob.iter()
+        .enumerate()
+        .map(|(idx, log10)| [idx as f64 / last, -log10 * std::f64::consts::LN_10 / n as f64])
+        .collect()
+}
+
+/// Draws `annotation` in plot coordinates
+fn show_annotation(plot_ui: &mut PlotUi, annotation: &Annotation, color: Color32, linewidth: f32)
+{
+    let [x1, y1] = annotation.from;
+    let [x2, y2] = annotation.to;
+    let text = annotation.text.as_str();
+    match annotation.kind {
+        AnnotationKind::Text => {
+            plot_ui.text(Text::new(PlotPoint::new(x1, y1), text).color(color));
+        },
+        AnnotationKind::Arrow => {
+            plot_ui.arrows(
+                Arrows::new(vec![annotation.from], vec![annotation.to])
+                    .color(color)
+            );
+            if !text.is_empty() {
+                plot_ui.text(
+                    Text::new(PlotPoint::new(x1, y1), text)
+                        .anchor(Align2::CENTER_BOTTOM)
+                        .color(color)
+                );
+            }
+        },
+        AnnotationKind::Region => {
+            let corners = vec![[x1, y1], [x2, y1], [x2, y2], [x1, y2]];
+            plot_ui.polygon(
+                Polygon::new(corners)
+                    .stroke(Stroke::new(linewidth, color))
+                    .fill_color(color.gamma_multiply(0.15))
+            );
+            if !text.is_empty() {
+                plot_ui.text(
+                    Text::new(PlotPoint::new((x1 + x2) * 0.5, y1.max(y2)), text)
+                        .anchor(Align2::CENTER_BOTTOM)
+                        .color(color)
+                );
+            }
+        }
+    }
+}
+
+/// Clicked positions are rounded to keep annotation files readable
+fn round_coordinate(value: f64) -> f64
+{
+    (value * 1000.0).round() / 1000.0
+}
+
+fn step_info_ui(ui: &mut egui::Ui, id: &str, info: &StepInfo<EnsembleMove>, t: &Texts)
+{
+    egui::Grid::new(id)
+        .num_columns(2)
+        .striped(true)
+        .show(
+            ui,
+            |ui|
+            {
+                ui.label(t.proposed_move);
+                ui.label(format!("{:?}", info.moves));
+                ui.end_row();
+                ui.label(t.energy_old_new);
+                ui.label(format!("{} → {}", info.old_energy, info.new_energy));
+                ui.end_row();
+                ui.label("log g(old) - log g(new)");
+                ui.label(format!("{:.4} - {:.4}", info.log_g_old, info.log_g_new));
+                ui.end_row();
+                ui.label(t.acceptance);
+                ui.label(format!("min(1, exp({:.4})) = {:.4}", info.log_g_old - info.log_g_new, info.acceptance.min(1.0)));
+                ui.end_row();
+                ui.label(t.random_number);
+                ui.label(format!("{:.4}", info.random));
+                ui.end_row();
+                ui.label(t.result);
+                ui.label(if info.accepted { t.accepted } else { t.rejected });
+                ui.end_row();
+                ui.label(t.histogram);
+                ui.label(format!("H({}) = {}", info.bin, info.hits));
+                ui.end_row();
+                if info.log_f > 0.0 {
+                    ui.label(t.density);
+                    ui.label(format!("log g({}) += {:e} → {:.4}", info.bin, info.log_f, info.log_g_bin));
+                    ui.end_row();
+                    if info.log_f_after != info.log_f {
+                        ui.label(t.log_f_refined);
+                        ui.label(format!("{:e} → {:e}", info.log_f, info.log_f_after));
+                        ui.end_row();
+                    }
+                } else {
+                    ui.label(t.density);
+                    ui.label(format!("log g({}) = {:.4}", info.bin, info.log_g_bin));
+                    ui.end_row();
+                }
+            }
+        );
+}
 
-fn exchange(c: char) -> char
+pub fn exchange(c: char) -> char
 {
     
     let super_list = [
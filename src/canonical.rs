@@ -0,0 +1,108 @@
+use crate::ln_sum_exp;
+
+/// Distribution of the energy in the canonical ensemble
+pub struct Canonical{
+    /// Probability of each energy
+    pub prob: Vec<f64>,
+    pub mean: f64,
+    pub variance: f64
+}
+
+impl Canonical{
+    /// Reweights a normalized density of states in log10 to `g(E) exp(-beta E)`.
+    /// NaN entries are treated as impossible energies
+    pub fn reweight(log10_density: &[f64], beta: f64) -> Option<Self>
+    {
+        let log_weights: Vec<f64> = log10_density.iter()
+            .enumerate()
+            .map(
+                |(energy, log10)|
+                {
+                    if log10.is_nan() {
+                        f64::NEG_INFINITY
+                    } else {
+                        log10 * std::f64::consts::LN_10 - beta * energy as f64
+                    }
+                }
+            ).collect();
+        let norm = ln_sum_exp(log_weights.iter().copied());
+        if !norm.is_finite() {
+            return None;
+        }
+        let prob = log_weights.into_iter()
+            .map(|log_weight| (log_weight - norm).exp())
+            .collect();
+        Some(Self::from_prob(prob))
+    }
+
+    /// Distribution of the energies that were sampled directly
+    pub fn from_hist(hist: &[usize]) -> Option<Self>
+    {
+        let total: usize = hist.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let rec = (total as f64).recip();
+        let prob = hist.iter()
+            .map(|hits| *hits as f64 * rec)
+            .collect();
+        Some(Self::from_prob(prob))
+    }
+
+    fn from_prob(prob: Vec<f64>) -> Self
+    {
+        let mean: f64 = prob.iter()
+            .enumerate()
+            .map(|(energy, p)| energy as f64 * p)
+            .sum();
+        let variance = prob.iter()
+            .enumerate()
+            .map(|(energy, p)| (energy as f64 - mean).powi(2) * p)
+            .sum();
+        Self{
+            prob,
+            mean,
+            variance
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{ln_binomial, normalized_log10};
+
+    #[test]
+    fn coins_match_closed_form()
+    {
+        let n = 50;
+        let density = normalized_log10(&ln_binomial(n));
+        for beta in [0.0, 0.7, -1.3] {
+            let canonical = Canonical::reweight(&density, beta).unwrap();
+            // every coin is independently heads with probability p
+            let p = (-beta).exp() / (1.0 + (-beta).exp());
+            let mean = n as f64 * p;
+            let variance = n as f64 * p * (1.0 - p);
+            assert!((canonical.mean - mean).abs() < 1e-9, "beta {beta}");
+            assert!((canonical.variance - variance).abs() < 1e-9, "beta {beta}");
+            assert!((canonical.prob.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn impossible_and_missing_energies()
+    {
+        // NaN bins are impossible energies
+        let canonical = Canonical::reweight(&[f64::NAN, 0.0, f64::NAN, 0.0], 0.0).unwrap();
+        assert_eq!(canonical.prob, vec![0.0, 0.5, 0.0, 0.5]);
+        assert_eq!(canonical.mean, 2.0);
+        assert_eq!(canonical.variance, 1.0);
+        assert!(Canonical::reweight(&[f64::NAN], 1.0).is_none());
+        assert!(Canonical::reweight(&[], 1.0).is_none());
+
+        let sampled = Canonical::from_hist(&[1, 0, 3]).unwrap();
+        assert_eq!(sampled.mean, 1.5);
+        assert_eq!(sampled.variance, 0.75);
+        assert!(Canonical::from_hist(&[0, 0]).is_none());
+    }
+}
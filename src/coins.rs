@@ -1,34 +1,115 @@
 use egui::mutex::Mutex;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use rand::distributions::Uniform;
 use rand_pcg::Pcg64;
 use rand::distributions::Distribution;
-use sampling::{*, examples::coin_flips::*};
-use statrs::distribution::{Binomial, Discrete};
-use std::f64::consts::LOG10_E;
+use sampling::*;
 use std::sync::{RwLock, Arc};
+use std::{thread, time::Instant};
 use sampling::HistUsizeFast;
+use crate::{Model, Ensemble, ModelKind, Metropolis, WangLandau, Entropic, WlParams, RefineSchedule, Handoff};
+use crate::{ln_binomial, normalized_log10};
 
-type Wlc = Arc<RwLock<WangLandau1T<HistogramFast<usize>, rand_pcg::Lcg128Xsl64, CoinFlipSequence<rand_pcg::Lcg128Xsl64>, CoinFlipMove, (), usize>>>;
-type Ent = EntropicSampling<HistogramFast<usize>, rand_pcg::Lcg128Xsl64, CoinFlipSequence<rand_pcg::Lcg128Xsl64>, CoinFlipMove, (), usize>;
+type Wlc = Arc<RwLock<WangLandau<Ensemble>>>;
+type Ent = Entropic<Ensemble>;
+
+/// Flipping a coin again undoes the move
+#[derive(Clone, Copy, Debug)]
+pub struct CoinFlipMove{
+    pub index: usize
+}
+
+#[derive(Clone)]
+pub struct CoinFlipSequence{
+    heads: Vec<bool>,
+    head_count: usize
+}
+
+impl CoinFlipSequence{
+    pub fn new(n: usize, rng: &mut Pcg64) -> Self
+    {
+        let heads: Vec<bool> = (0..n)
+            .map(|_| rng.gen())
+            .collect();
+        let head_count = heads.iter()
+            .filter(|head| **head)
+            .count();
+        Self{
+            heads,
+            head_count
+        }
+    }
+
+    fn flip(&mut self, index: usize)
+    {
+        let coin = &mut self.heads[index];
+        *coin = !*coin;
+        if *coin {
+            self.head_count += 1;
+        } else {
+            self.head_count -= 1;
+        }
+    }
+}
+
+impl Model for CoinFlipSequence{
+    type Move = CoinFlipMove;
+
+    fn energy(&self) -> usize
+    {
+        self.head_count
+    }
+
+    fn num_bins(&self) -> usize
+    {
+        self.heads.len() + 1
+    }
+
+    fn propose(&mut self, rng: &mut Pcg64) -> CoinFlipMove
+    {
+        let index = rng.gen_range(0..self.heads.len());
+        self.flip(index);
+        CoinFlipMove { index }
+    }
+
+    fn undo(&mut self, m: &CoinFlipMove)
+    {
+        self.flip(m.index);
+    }
+
+    fn exact_log_density(&self) -> Option<Vec<f64>>
+    {
+        Some(ln_binomial(self.heads.len()))
+    }
+
+    fn sample_energy(&self, rng: &mut Pcg64) -> usize
+    {
+        // a random byte is negative with probability 1/2
+        let dist = Uniform::new_inclusive(i8::MIN, i8::MAX);
+        dist.sample_iter(rng)
+            .take(self.heads.len())
+            .filter(|val| *val < 0)
+            .count()
+    }
+}
 
 
 pub struct Simple{
     rng: Pcg64,
-    n: usize,
+    ensemble: Ensemble,
     pub hist: HistUsizeFast
 }
 
 impl Simple{
-    pub fn new(n: usize, seed: u64) -> Self
+    pub fn new(ensemble: Ensemble, seed: u64) -> Self
     {
         let rng = Pcg64::seed_from_u64(seed);
-        let hist = HistUsizeFast::new_inclusive(0, n)
+        let hist = HistUsizeFast::new_inclusive(0, ensemble.num_bins() - 1)
             .unwrap();
 
         Self{
             rng,
-            n,
+            ensemble,
             hist
         }
     }
@@ -36,23 +117,23 @@ impl Simple{
     pub fn sample_while<F>(&mut self, mut cond: F)
     where F: FnMut() -> bool
     {
-        let dist = Uniform::new_inclusive(i8::MIN, i8::MAX);
-        let mut iter = dist.sample_iter(&mut self.rng);
         while cond()
         {
             for _ in 0..8 {
-                let mut count = 0;
-                for val in (&mut iter).take(self.n)
-                {
-                    if val < 0 {
-                        count += 1;
-                    }
-                }
-                self.hist.increment_quiet(count);
+                let energy = self.ensemble.sample_energy(&mut self.rng);
+                self.hist.increment_quiet(energy);
             }
         }
     }
 
+    /// Draws a single independent state and returns its energy
+    pub fn sample_once(&mut self) -> usize
+    {
+        let energy = self.ensemble.sample_energy(&mut self.rng);
+        self.hist.increment_quiet(energy);
+        energy
+    }
+
     pub fn get_prob(&self) -> Vec<f64>
     {
         let total: usize = self.hist.hist().iter().sum();
@@ -67,71 +148,198 @@ impl Simple{
                 }
             ).collect()
     }
+}
 
-    pub fn get_prob_log10(&self) -> Vec<f64>
+/// How much the samplers should do during one frame
+#[derive(Clone, Copy)]
+pub enum Work{
+    /// Sample until the time in milliseconds passed
+    Millis(f32),
+    /// Perform this many Markov steps. Simple sampling draws 
+    /// about as many coin flips or dice rolls instead
+    Steps(usize)
+}
+
+impl Work{
+    fn budget(self, start: Instant, scale: usize) -> impl FnMut() -> bool
     {
-        let mut v = self.get_prob();
-        v.iter_mut()
-            .for_each(
-                |val|
-                {
-                    if *val > 0.0 {
-                        *val = val.log10();
-                    } else {
-                        *val = f64::NAN;
-                    }
+        let mut steps = 0;
+        move || {
+            match self {
+                Work::Millis(millis) => (start.elapsed().as_millis() as f32) < millis,
+                Work::Steps(max) => {
+                    steps += 1;
+                    steps <= (max / scale).max(1)
                 }
-            );
-        v
+            }
+        }
     }
 }
 
 pub struct CoinSeq{
+    pub kind: ModelKind,
+    /// System size, i.e., number of coins, dice or steps
+    pub n: usize,
     pub wl: Wlc,
+    /// Exact probability in log10, empty until `exact` is finished
+    /// or if there is no exact result
     pub log_prob_true: Vec<f64>,
-    pub entr: Ent,
-    pub simple: Arc<Mutex<Simple>>
+    /// Calculation of the exact result, which may take a while for large systems
+    exact: Option<thread::JoinHandle<Vec<f64>>>,
+    pub entr: Option<Ent>,
+    pub simple: Arc<Mutex<Simple>>,
+    /// Biased Markov chain for comparison, only runs if requested
+    pub metropolis: Option<Arc<Mutex<Metropolis<Ensemble>>>>,
+    pub seed: u64
+}
+
+impl CoinSeq{
+    /// Takes the exact result once its calculation is finished
+    pub fn poll_exact(&mut self)
+    {
+        if self.exact.as_ref().is_some_and(|handle| handle.is_finished()) {
+            self.log_prob_true = self.exact
+                .take()
+                .unwrap()
+                .join()
+                .unwrap();
+        }
+    }
+
+    /// Starts the entropic sampling if the Wang-Landau simulation
+    /// reached the stage requested by the schedule.
+    /// A running entropic sampling uses the refinements of `schedule` from now on.
+    /// Returns true if the entropic sampling was started by this call
+    pub fn try_start_entropic(&mut self, schedule: RefineSchedule) -> bool
+    {
+        if let Some(entr) = self.entr.as_mut() {
+            entr.set_schedule(schedule);
+            return false;
+        }
+        let mut wl = self.wl.write().unwrap();
+        let start = match schedule.handoff {
+            Handoff::Immediately => true,
+            Handoff::LogF(log_f) => wl.log_f() <= log_f || wl.is_finished(),
+            Handoff::Finished => wl.is_finished(),
+            Handoff::Manual => false
+        };
+        if start {
+            self.entr = Some(Entropic::from_wl(&mut wl, schedule));
+        }
+        start
+    }
+
+    /// Starts, restarts or stops the Metropolis chain. 
+    /// The chain is restarted if `beta` differs from the current one
+    pub fn set_metropolis(&mut self, beta: Option<f64>)
+    {
+        let Some(beta) = beta else {
+            self.metropolis = None;
+            return;
+        };
+        let current = self.metropolis
+            .as_ref()
+            .map(|m| m.lock().beta());
+        if current != Some(beta) {
+            let mut rng = Pcg64::seed_from_u64(self.seed);
+            let ensemble = Ensemble::new(self.kind, self.n, &mut rng);
+            self.metropolis = Some(
+                Arc::new(Mutex::new(Metropolis::new(ensemble, rng, beta)))
+            );
+        }
+    }
+
+    /// Advance all samplers. Wang-Landau, simple sampling and Metropolis run in their own threads
+    pub fn step(&mut self, work: Work)
+    {
+        let time = Instant::now();
+        let wl = self.wl.clone();
+        let t = thread::spawn(
+            move || {
+                wl.write().unwrap().wang_landau_while(work.budget(time, 1));
+            }
+        );
+
+        let simp = self.simple.clone();
+        // every call of the condition corresponds to 8 samples of n coins, dice or steps
+        let scale = 8 * self.n.max(1);
+        let t2 = thread::spawn(
+            move ||
+            {
+                simp.lock().sample_while(work.budget(time, scale))
+            }
+        );
+
+        let t3 = self.metropolis
+            .clone()
+            .map(
+                |metropolis|
+                {
+                    thread::spawn(
+                        move || metropolis.lock().metropolis_while(work.budget(time, 1))
+                    )
+                }
+            );
+
+        if let Some(entr) = self.entr.as_mut() {
+            entr.entropic_sampling_while(work.budget(time, 1));
+        }
+
+        t.join().unwrap();
+        t2.join().unwrap();
+        if let Some(t3) = t3 {
+            t3.join().unwrap();
+        }
+    }
+
+    /// Replaces the entropic sampling by a new one, 
+    /// starting from the current state of the Wang-Landau simulation
+    pub fn branch_entropic(&mut self, schedule: RefineSchedule)
+    {
+        let mut wl = self.wl.write().unwrap();
+        self.entr = Some(Entropic::from_wl(&mut wl, schedule));
+    }
 }
 
-pub fn generate_cs(n: usize, seed: u64, step_size: usize, threshold: f64) -> CoinSeq
+pub fn generate_cs(kind: ModelKind, n: usize, seed: u64, params: WlParams, schedule: RefineSchedule) -> CoinSeq
 {
-    let hist = HistUsizeFast::new_inclusive(0, n).unwrap();
     let mut rng = Pcg64::seed_from_u64(seed);
-    let ensemble = CoinFlipSequence::new(
+    let ensemble = Ensemble::new(
+        kind,
         n,
-        Pcg64::from_rng(&mut rng).unwrap()
+        &mut rng
     );
 
-    let mut wl: WangLandau1T<HistogramFast<usize>, rand_pcg::Lcg128Xsl64, CoinFlipSequence<rand_pcg::Lcg128Xsl64>, CoinFlipMove, (), usize> = WangLandau1T::new(
-        threshold, // arbitrary threshold for `log_f`(see paper), 
-                 // you have to try what is good for your model
-        ensemble,
-        Pcg64::from_rng(&mut rng).unwrap(),
-        step_size,  // stepsize 1 is sufficient for this problem
-        hist,
-        100 // every 100 steps: check if WL can refine factor f
-    ).unwrap();
-
-    wl.init_greedy_heuristic(
-        |coin_seq| Some(coin_seq.head_count()),
-        Some(10_000) // if no valid state is found after 10_000 
-                     // this returns an Err. If you do not want a step limit,
-                     // you can use None here
-    ).expect("Unable to find valid state within 10_000 steps!");
-
-    let ent = EntropicSampling::from_wl(wl.clone()).unwrap();
+    // the exact result is calculated in the background, so the sampling starts right away
+    let exact_ensemble = ensemble.clone();
+    let exact = thread::spawn(
+        move ||
+        {
+            exact_ensemble.exact_log_density()
+                .map(|log_density| normalized_log10(&log_density))
+                .unwrap_or_default()
+        }
+    );
 
-    let binomial = Binomial::new(0.5, n as u64).unwrap();
-    let log_prob_true: Vec<_> = (0..=n)
-        .map(|k| LOG10_E*binomial.ln_pmf(k as u64))
-        .collect();
+    let simp = Simple::new(ensemble.clone(), seed);
 
-    let simp = Simple::new(n, seed);
+    let wl = WangLandau::new(
+        ensemble,
+        Pcg64::from_rng(&mut rng).unwrap(),
+        params
+    );
 
-    CoinSeq { wl: Arc::new(
-        RwLock::new(wl)), 
-        log_prob_true, 
-        entr: ent,
-        simple: Arc::new(Mutex::new(simp)) 
-    }
+    let mut cs = CoinSeq { 
+        kind,
+        n,
+        wl: Arc::new(RwLock::new(wl)), 
+        log_prob_true: Vec::new(),
+        exact: Some(exact),
+        entr: None,
+        simple: Arc::new(Mutex::new(simp)),
+        metropolis: None,
+        seed
+    };
+    cs.try_start_entropic(schedule);
+    cs
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+use rand::Rng;
+use rand_pcg::Pcg64;
+use crate::{Model, independent_units};
+
+/// Re-rolling a die. Undone by putting back the previous face
+#[derive(Clone, Copy, Debug)]
+pub struct DiceMove{
+    pub index: usize,
+    pub previous: usize
+}
+
+/// N dice, the energy is the sum of the eyes minus N,
+/// i.e., it is in `0..=N*(faces-1)`
+#[derive(Clone)]
+pub struct Dice{
+    faces: usize,
+    /// eyes minus one of each die
+    values: Vec<usize>,
+    sum: usize
+}
+
+impl Dice{
+    pub fn new(n: usize, faces: usize, rng: &mut Pcg64) -> Self
+    {
+        assert!(faces >= 2, "A die needs at least 2 faces");
+        let values: Vec<usize> = (0..n)
+            .map(|_| rng.gen_range(0..faces))
+            .collect();
+        let sum = values.iter().sum();
+        Self{
+            faces,
+            values,
+            sum
+        }
+    }
+
+    fn set(&mut self, index: usize, value: usize)
+    {
+        self.sum -= self.values[index];
+        self.sum += value;
+        self.values[index] = value;
+    }
+}
+
+impl Model for Dice{
+    type Move = DiceMove;
+
+    fn energy(&self) -> usize
+    {
+        self.sum
+    }
+
+    fn num_bins(&self) -> usize
+    {
+        self.values.len() * (self.faces - 1) + 1
+    }
+
+    fn propose(&mut self, rng: &mut Pcg64) -> DiceMove
+    {
+        let index = rng.gen_range(0..self.values.len());
+        let previous = self.values[index];
+        self.set(index, rng.gen_range(0..self.faces));
+        DiceMove { index, previous }
+    }
+
+    fn undo(&mut self, m: &DiceMove)
+    {
+        self.set(m.index, m.previous);
+    }
+
+    fn exact_log_density(&self) -> Option<Vec<f64>>
+    {
+        // a single die has exactly one state for each energy
+        let die = vec![0.0; self.faces];
+        Some(independent_units(&die, self.values.len()))
+    }
+
+    fn sample_energy(&self, rng: &mut Pcg64) -> usize
+    {
+        (0..self.values.len())
+            .map(|_| rng.gen_range(0..self.faces))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use rand::SeedableRng;
+    use crate::enumerate;
+
+    #[test]
+    fn exact_density()
+    {
+        let mut rng = Pcg64::seed_from_u64(3);
+        let dice = Dice::new(4, 5, &mut rng);
+        let exact = dice.exact_log_density().unwrap();
+        let brute_force = enumerate(4, 5, dice.num_bins(), |faces| faces.iter().sum()).unwrap();
+        for (a, b) in exact.iter().zip(brute_force) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+}
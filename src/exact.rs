@@ -0,0 +1,252 @@
+//! Exact densities of states as reference for the samplers.
+//!
+//! All densities are stored as natural logarithm of the number of states,
+//! so that even the tails of large systems do neither overflow nor underflow.
+//! Impossible energies are `f64::NEG_INFINITY`
+//!
+//! The app uses closed forms and convolutions, which exist for every model.
+//! Enumeration and the transfer matrix method are only compiled for the tests,
+//! where they check those results for small systems
+
+/// Largest number of bins for which an exact result is calculated, for every model.
+/// The convolutions take a time quadratic in the number of bins,
+/// the limit is checked in `Ensemble::exact_log_density`
+pub const MAX_EXACT_BINS: usize = 1 << 15;
+
+/// ln(exp(a) + exp(b))
+pub fn ln_add_exp(a: f64, b: f64) -> f64
+{
+    let (max, min) = if a > b {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    if min == f64::NEG_INFINITY {
+        max
+    } else {
+        max + (min - max).exp().ln_1p()
+    }
+}
+
+/// ln(sum_i exp(vals_i))
+pub fn ln_sum_exp<I>(vals: I) -> f64
+where I: IntoIterator<Item = f64>
+{
+    vals.into_iter()
+        .fold(f64::NEG_INFINITY, ln_add_exp)
+}
+
+/// ln(n choose k) for all k in `0..=n`, i.e., the density of states of n coins.
+/// Unlike [`independent_units`] it only takes a time linear in n
+pub fn ln_binomial(n: usize) -> Vec<f64>
+{
+    let mut res = Vec::with_capacity(n + 1);
+    res.push(0.0);
+    for k in 1..=n {
+        let last = res[k - 1];
+        res.push(last + ((n - k + 1) as f64 / k as f64).ln());
+    }
+    res
+}
+
+/// Polynomial multiplication in log space, i.e., the density of states
+/// of two independent systems whose energies add up
+pub fn convolve(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut res = vec![f64::NEG_INFINITY; a.len() + b.len() - 1];
+    for (i, val_a) in a.iter().enumerate() {
+        if *val_a == f64::NEG_INFINITY {
+            continue;
+        }
+        for (val_b, r) in b.iter().zip(res[i..].iter_mut()) {
+            *r = ln_add_exp(*r, val_a + val_b);
+        }
+    }
+    res
+}
+
+/// Density of states of `units` independent and identical units.
+/// `unit` is the density of states of a single unit
+pub fn independent_units(unit: &[f64], units: usize) -> Vec<f64>
+{
+    // exponentiation by squaring
+    let mut res = vec![0.0];
+    let mut power = unit.to_vec();
+    let mut remaining = units;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            res = convolve(&res, &power);
+        }
+        remaining >>= 1;
+        if remaining > 0 {
+            power = convolve(&power, &power);
+        }
+    }
+    res
+}
+
+/// Transfer matrix method: `initial` is the density of states
+/// over the states of the transfer matrix after zero steps.
+/// `transfer(state, target)` returns the log weight of going from `state` to `target`
+/// or `None` if that is impossible. Returns the density after `steps` steps
+#[cfg(test)]
+pub fn transfer_matrix<F>(initial: Vec<f64>, steps: usize, mut transfer: F) -> Vec<f64>
+where F: FnMut(usize, usize) -> Option<f64>
+{
+    let states = initial.len();
+    let mut current = initial;
+    for _ in 0..steps {
+        let mut next = vec![f64::NEG_INFINITY; states];
+        for (state, val) in current.iter().enumerate() {
+            if *val == f64::NEG_INFINITY {
+                continue;
+            }
+            for (target, n) in next.iter_mut().enumerate() {
+                if let Some(weight) = transfer(state, target) {
+                    *n = ln_add_exp(*n, val + weight);
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// Counts all `choices^units` configurations. Only feasible for small systems,
+/// returns `None` if there are more than `2^24` configurations.
+/// `energy` gets the choice of each unit and returns the bin, which has to be smaller than `bins`.
+/// The tests use it as brute force reference for the models
+#[cfg(test)]
+pub fn enumerate<F>(units: usize, choices: usize, bins: usize, mut energy: F) -> Option<Vec<f64>>
+where F: FnMut(&[usize]) -> usize
+{
+    let total = (choices as f64).powi(units as i32);
+    if total > (1 << 24) as f64 {
+        return None;
+    }
+    let mut counts = vec![0_u64; bins];
+    let mut config = vec![0; units];
+    'outer: loop {
+        counts[energy(&config)] += 1;
+        for c in config.iter_mut() {
+            *c += 1;
+            if *c < choices {
+                continue 'outer;
+            }
+            *c = 0;
+        }
+        break;
+    }
+    Some(
+        counts.into_iter()
+            .map(|count| (count as f64).ln())
+            .collect()
+    )
+}
+
+/// Turns a density of states in natural log into a normalized probability in log10.
+/// Impossible energies become NaN, so they are not drawn
+pub fn normalized_log10(log_density: &[f64]) -> Vec<f64>
+{
+    let norm = ln_sum_exp(log_density.iter().copied());
+    log_density.iter()
+        .map(
+            |val| 
+            {
+                if *val == f64::NEG_INFINITY {
+                    f64::NAN
+                } else {
+                    (val - norm) * std::f64::consts::LOG10_E
+                }
+            }
+        ).collect()
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn assert_close(a: &[f64], b: &[f64])
+    {
+        assert_eq!(a.len(), b.len(), "{a:?} {b:?}");
+        for (x, y) in a.iter().zip(b) {
+            assert!(x == y || (x - y).abs() < 1e-9, "{a:?} {b:?}");
+        }
+    }
+
+    #[test]
+    fn ln_add_exp_with_neg_infinity()
+    {
+        let neg_inf = f64::NEG_INFINITY;
+        assert_eq!(ln_add_exp(neg_inf, 1.5), 1.5);
+        assert_eq!(ln_add_exp(1.5, neg_inf), 1.5);
+        assert_eq!(ln_add_exp(neg_inf, neg_inf), neg_inf);
+        assert!((ln_add_exp(0.0, 0.0) - 2_f64.ln()).abs() < 1e-15);
+    }
+
+    #[test]
+    fn convolve_small()
+    {
+        // (1 + 2x) * (3 + x^2) = 3 + 6x + x^2 + 2x^3
+        let a = [0.0, 2_f64.ln()];
+        let b = [3_f64.ln(), f64::NEG_INFINITY, 0.0];
+        let expected = [3_f64.ln(), 6_f64.ln(), 0.0, 2_f64.ln()];
+        assert_close(&convolve(&a, &b), &expected);
+        assert!(convolve(&a, &[]).is_empty());
+    }
+
+    #[test]
+    fn coins_are_binomial()
+    {
+        for n in [0, 1, 7, 30] {
+            assert_close(&independent_units(&[0.0, 0.0], n), &ln_binomial(n));
+        }
+        // 1000 choose 500 ≈ 2.702882409454366e299
+        let ln_binomial = ln_binomial(1000);
+        assert!((ln_binomial[500] - 2.702882409454366e299_f64.ln()).abs() < 1e-9);
+        assert_eq!(ln_binomial[0], 0.0);
+        assert!(ln_binomial[1000].abs() < 1e-9);
+    }
+
+    #[test]
+    fn enumerate_counts_configurations()
+    {
+        // two dice with faces 0, 1, 2
+        let density = enumerate(2, 3, 5, |config| config.iter().sum()).unwrap();
+        let counts: Vec<f64> = [1.0_f64, 2.0, 3.0, 2.0, 1.0].iter().map(|c| c.ln()).collect();
+        assert_close(&density, &counts);
+        assert_close(&independent_units(&[0.0, 0.0, 0.0], 2), &counts);
+        assert!(enumerate(25, 2, 26, |_| 0).is_none());
+    }
+
+    #[test]
+    fn transfer_matrix_of_coins()
+    {
+        // the state is the number of heads so far
+        let n = 12;
+        let mut initial = vec![f64::NEG_INFINITY; n + 1];
+        initial[0] = 0.0;
+        let density = transfer_matrix(
+            initial,
+            n,
+            |state, target| (target == state || target == state + 1).then_some(0.0)
+        );
+        assert_close(&density, &independent_units(&[0.0, 0.0], n));
+    }
+
+    #[test]
+    fn normalized_log10_sums_to_1()
+    {
+        let density = [0.0, f64::NEG_INFINITY, 3_f64.ln(), 1.0];
+        let normalized = normalized_log10(&density);
+        assert!(normalized[1].is_nan());
+        let sum: f64 = normalized.iter()
+            .filter(|val| !val.is_nan())
+            .map(|val| 10_f64.powf(*val))
+            .sum();
+        assert!((sum - 1.0).abs() < 1e-12, "sum {sum}");
+    }
+}
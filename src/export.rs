@@ -0,0 +1,206 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+use std::time::{Duration, Instant};
+use egui::{ColorImage, Rect};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    imageops::{self, FilterType},
+    Delay, Frame, ImageResult, RgbaImage
+};
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum FrameFormat{
+    /// `path` is a directory, the frames are stored as `frame_00000.png` etc.
+    PngSequence,
+    /// `path` is the gif file
+    Gif
+}
+
+pub struct ExportSettings{
+    pub format: FrameFormat,
+    pub path: String,
+    pub fps: u32,
+    pub frames: usize,
+    /// Markov steps of each sampler between two frames
+    pub steps_per_frame: usize,
+    /// Size of the frames in pixels, independent of the size of the window
+    pub width: u32,
+    pub height: u32
+}
+
+impl Default for ExportSettings{
+    fn default() -> Self {
+        Self {
+            format: FrameFormat::Gif,
+            path: "wang_landau.gif".to_owned(),
+            fps: 10,
+            frames: 100,
+            steps_per_frame: 100000,
+            width: 1280,
+            height: 720
+        }
+    }
+}
+
+/// How long to wait for a requested screenshot before the export is aborted
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Writes screenshots of the central panel as animation frames.
+/// The simulation is advanced by a fixed number of steps per frame,
+/// so the animation only depends on the seed and the settings.
+///
+/// The frames are screenshots of the window, not an offscreen rendering.
+/// They are scaled to the size of the settings, so every frame has the same size,
+/// but the window has to stay visible: a minimized or hidden window does not
+/// deliver screenshots, see [`Exporter::timed_out`]
+pub struct Exporter{
+    format: FrameFormat,
+    path: PathBuf,
+    fps: u32,
+    frames: usize,
+    pub steps_per_frame: usize,
+    width: u32,
+    height: u32,
+    frame: usize,
+    /// Area of the screenshot that is stored and when it was requested,
+    /// if a screenshot was requested
+    pending: Option<(Rect, Instant)>,
+    gif: Option<GifEncoder<BufWriter<File>>>
+}
+
+impl Exporter{
+    pub fn new(settings: &ExportSettings) -> ImageResult<Self>
+    {
+        let path = PathBuf::from(&settings.path);
+        let gif = match settings.format {
+            FrameFormat::Gif => {
+                let file = File::create(&path)?;
+                let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), 10);
+                encoder.set_repeat(Repeat::Infinite)?;
+                Some(encoder)
+            },
+            FrameFormat::PngSequence => {
+                std::fs::create_dir_all(&path)?;
+                None
+            }
+        };
+        Ok(
+            Self{
+                format: settings.format,
+                path,
+                fps: settings.fps.max(1),
+                frames: settings.frames,
+                steps_per_frame: settings.steps_per_frame,
+                width: settings.width.max(1),
+                height: settings.height.max(1),
+                frame: 0,
+                pending: None,
+                gif
+            }
+        )
+    }
+
+    /// Run time of the animation in seconds
+    pub fn time(&self) -> f64
+    {
+        self.frame as f64 / self.fps as f64
+    }
+
+    pub fn frame(&self) -> usize
+    {
+        self.frame
+    }
+
+    pub fn frames(&self) -> usize
+    {
+        self.frames
+    }
+
+    pub fn is_finished(&self) -> bool
+    {
+        self.frame >= self.frames
+    }
+
+    pub fn is_waiting(&self) -> bool
+    {
+        self.pending.is_some()
+    }
+
+    /// Remember which part of the next screenshot is stored
+    pub fn request(&mut self, rect: Rect)
+    {
+        self.pending = Some((rect, Instant::now()));
+    }
+
+    /// A requested screenshot did not arrive in time, e.g., because the window is minimized
+    pub fn timed_out(&self) -> bool
+    {
+        self.pending
+            .is_some_and(|(_, requested)| requested.elapsed() > SCREENSHOT_TIMEOUT)
+    }
+
+    pub fn write(&mut self, screenshot: &ColorImage, pixels_per_point: f32) -> ImageResult<()>
+    {
+        let rect = match self.pending.take() {
+            Some((rect, _)) => rect,
+            None => return Ok(())
+        };
+        let image = fit(&crop(screenshot, rect, pixels_per_point), self.width, self.height);
+        match self.gif.as_mut() {
+            Some(encoder) => {
+                let delay = Delay::from_numer_denom_ms(1000, self.fps);
+                encoder.encode_frame(Frame::from_parts(image, 0, 0, delay))?;
+            },
+            None => {
+                debug_assert!(self.format == FrameFormat::PngSequence);
+                let name = format!("frame_{:05}.png", self.frame);
+                image.save(self.path.join(name))?;
+            }
+        }
+        self.frame += 1;
+        if self.is_finished() {
+            // dropping the encoder writes the end of the gif
+            self.gif = None;
+        }
+        Ok(())
+    }
+}
+
+/// Scales `image` to fit into `width` x `height` and centers it.
+/// The margins get the color of the top left pixel, i.e., of the panel background
+fn fit(image: &RgbaImage, width: u32, height: u32) -> RgbaImage
+{
+    let background = image.get_pixel_checked(0, 0)
+        .copied()
+        .unwrap_or(image::Rgba([255, 255, 255, 255]));
+    let mut frame = RgbaImage::from_pixel(width, height, background);
+    if image.width() == 0 || image.height() == 0 {
+        return frame;
+    }
+    let scale = (width as f64 / image.width() as f64).min(height as f64 / image.height() as f64);
+    let scaled_width = ((image.width() as f64 * scale).round() as u32).clamp(1, width);
+    let scaled_height = ((image.height() as f64 * scale).round() as u32).clamp(1, height);
+    let scaled = imageops::resize(image, scaled_width, scaled_height, FilterType::Triangle);
+    let x = (width - scaled_width) / 2;
+    let y = (height - scaled_height) / 2;
+    imageops::overlay(&mut frame, &scaled, x as i64, y as i64);
+    frame
+}
+
+fn crop(screenshot: &ColorImage, rect: Rect, pixels_per_point: f32) -> RgbaImage
+{
+    let [width, height] = screenshot.size;
+    let to_pixel = |val: f32, max: usize| ((val * pixels_per_point).round().max(0.0) as usize).min(max);
+    let min_x = to_pixel(rect.min.x, width);
+    let max_x = to_pixel(rect.max.x, width);
+    let min_y = to_pixel(rect.min.y, height);
+    let max_y = to_pixel(rect.max.y, height);
+
+    let mut bytes = Vec::with_capacity((max_x - min_x) * (max_y - min_y) * 4);
+    for row in min_y..max_y {
+        screenshot.pixels[row * width + min_x..row * width + max_x]
+            .iter()
+            .for_each(|color| bytes.extend_from_slice(&color.to_srgba_unmultiplied()));
+    }
+    RgbaImage::from_raw((max_x - min_x) as u32, (max_y - min_y) as u32, bytes)
+        .unwrap()
+}
@@ -0,0 +1,188 @@
+use egui::{Color32, ColorImage};
+
+/// Maximal number of rows of the heatmap, neighboring bins are summed up if there are more bins
+const MAX_ROWS: usize = 512;
+
+/// Stores the Wang-Landau histogram over time
+pub struct HistHistory{
+    /// Minimal run time between two columns in seconds.
+    /// Doubles every time the history is thinned out
+    interval: f64,
+    max_columns: usize,
+    times: Vec<f64>,
+    columns: Vec<Vec<usize>>
+}
+
+impl Default for HistHistory{
+    fn default() -> Self {
+        Self {
+            interval: 0.05,
+            max_columns: 1000,
+            times: Vec::new(),
+            columns: Vec::new()
+        }
+    }
+}
+
+impl HistHistory{
+    pub fn clear(&mut self)
+    {
+        *self = Self::default();
+    }
+
+    pub fn record(&mut self, time: f64, hist: &[usize])
+    {
+        let due = self.times
+            .last()
+            .is_none_or(|last| time - last >= self.interval);
+        if !due {
+            return;
+        }
+        if self.columns.len() >= self.max_columns {
+            // keep every second column, so the whole run stays visible
+            self.times = self.times.iter().step_by(2).copied().collect();
+            self.columns = self.columns.iter().step_by(2).cloned().collect();
+            self.interval *= 2.0;
+        }
+        let bins_per_row = hist.len().div_ceil(MAX_ROWS).max(1);
+        let column = hist.chunks(bins_per_row)
+            .map(|chunk| chunk.iter().sum())
+            .collect();
+        self.times.push(time);
+        self.columns.push(column);
+    }
+
+    /// Time covered by the columns up to `max_time`, i.e., start and end
+    pub fn time_range(&self, max_time: f64) -> Option<(f64, f64)>
+    {
+        let count = self.times.partition_point(|time| *time <= max_time);
+        if count == 0 {
+            return None;
+        }
+        Some((self.times[0], self.times[count - 1] + self.interval))
+    }
+
+    /// Heatmap of all columns up to `max_time`. Time goes from left to right,
+    /// the lowest bin is in the bottom row
+    pub fn to_image(&self, max_time: f64, log: bool) -> Option<ColorImage>
+    {
+        let count = self.times.partition_point(|time| *time <= max_time);
+        let columns = &self.columns[..count];
+        let rows = columns.first()?.len();
+        let scale = |hits: usize| {
+            if log {
+                (hits as f64).ln_1p()
+            } else {
+                hits as f64
+            }
+        };
+        let max = columns.iter()
+            .flatten()
+            .map(|hits| scale(*hits))
+            .fold(0.0, f64::max);
+
+        let mut image = ColorImage::new([count, rows], Color32::BLACK);
+        for (x, column) in columns.iter().enumerate() {
+            for (bin, hits) in column.iter().enumerate() {
+                let y = rows - 1 - bin;
+                let t = if max > 0.0 {
+                    scale(*hits) / max
+                } else {
+                    0.0
+                };
+                image.pixels[y * count + x] = colormap(t as f32);
+            }
+        }
+        Some(image)
+    }
+}
+
+/// Approximation of the viridis colormap, `t` in [0, 1]
+fn colormap(t: f32) -> Color32
+{
+    const STOPS: [[f32; 3]; 5] = [
+        [68.0, 1.0, 84.0],
+        [59.0, 82.0, 139.0],
+        [33.0, 145.0, 140.0],
+        [94.0, 201.0, 98.0],
+        [253.0, 231.0, 37.0]
+    ];
+    let t = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let idx = (t.floor() as usize).min(STOPS.len() - 2);
+    let frac = t - idx as f32;
+    let [r, g, b]: [u8; 3] = std::array::from_fn(
+        |i| (STOPS[idx][i] + (STOPS[idx + 1][i] - STOPS[idx][i]) * frac) as u8
+    );
+    Color32::from_rgb(r, g, b)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn neighboring_bins_are_summed()
+    {
+        let mut history = HistHistory::default();
+        history.record(0.0, &[1, 2, 3]);
+        assert_eq!(history.columns[0], vec![1, 2, 3]);
+
+        // 1030 bins need 3 bins per row, the last row only gets one bin
+        let hist: Vec<usize> = (0..1030).collect();
+        history.record(1.0, &hist);
+        let column = &history.columns[1];
+        assert_eq!(column.len(), 344);
+        assert!(column.len() <= MAX_ROWS);
+        assert_eq!(column[0], 1 + 2);
+        assert_eq!(column[1], 3 + 4 + 5);
+        assert_eq!(column[343], 1029);
+        assert_eq!(column.iter().sum::<usize>(), hist.iter().sum());
+    }
+
+    #[test]
+    fn thinning_keeps_whole_run()
+    {
+        let mut history = HistHistory { max_columns: 4, interval: 1.0, ..Default::default() };
+        // too early, not recorded
+        history.record(0.0, &[1]);
+        history.record(0.5, &[1]);
+        assert_eq!(history.times, vec![0.0]);
+        for time in 1..6 {
+            history.record(time as f64, &[time]);
+        }
+        // the fifth column thinned the first four out
+        assert_eq!(history.times, vec![0.0, 2.0, 4.0]);
+        assert_eq!(history.interval, 2.0);
+        history.record(5.0, &[5]);
+        assert_eq!(history.times.len(), 3);
+        history.record(6.0, &[6]);
+        assert_eq!(history.times, vec![0.0, 2.0, 4.0, 6.0]);
+        assert_eq!(history.time_range(5.0), Some((0.0, 6.0)));
+        assert_eq!(history.time_range(-1.0), None);
+    }
+
+    #[test]
+    fn scale_maps_hits_to_colors()
+    {
+        let mut history = HistHistory::default();
+        history.record(0.0, &[0, 3, 15]);
+        history.record(1.0, &[0, 0, 0]);
+
+        // the lowest bin is in the bottom row
+        let image = history.to_image(0.5, false).unwrap();
+        assert_eq!(image.size, [1, 3]);
+        assert_eq!(image.pixels[2], colormap(0.0));
+        assert_eq!(image.pixels[1], colormap(0.2));
+        assert_eq!(image.pixels[0], colormap(1.0));
+
+        // ln(1 + 3) is half of ln(1 + 15)
+        let image = history.to_image(1.0, true).unwrap();
+        assert_eq!(image.size, [2, 3]);
+        assert_eq!(image.pixels[2], colormap(0.5));
+        assert_eq!(image.pixels[0], colormap(1.0));
+        // a column without hits stays at the bottom of the colormap
+        assert!((0..3).all(|y| image.pixels[2 * y + 1] == colormap(0.0)));
+
+        assert!(history.to_image(-1.0, true).is_none());
+    }
+}
@@ -0,0 +1,683 @@
+//! German and English texts of the user interface.
+//!
+//! Texts containing `{}` are templates, see [`fill`]
+
+use std::fmt::Display;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Lang{
+    #[default]
+    De,
+    En
+}
+
+impl Lang{
+    pub fn texts(self) -> &'static Texts
+    {
+        match self {
+            Lang::De => &DE,
+            Lang::En => &EN
+        }
+    }
+
+    /// Name of the language in the language itself
+    pub fn name(self) -> &'static str
+    {
+        match self {
+            Lang::De => "Deutsch",
+            Lang::En => "English"
+        }
+    }
+}
+
+/// Replaces the `{}` in `template` by `args`, in order
+pub fn fill(template: &str, args: &[&dyn Display]) -> String
+{
+    let mut res = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    res.push_str(parts.next().unwrap());
+    for part in parts {
+        if let Some(arg) = args.next() {
+            res.push_str(&arg.to_string());
+        }
+        res.push_str(part);
+    }
+    res
+}
+
+pub struct Texts{
+    pub window_title: &'static str,
+    pub language: &'static str,
+    pub shortcuts: &'static str,
+    pub shortcuts_hover: &'static str,
+    pub start: &'static str,
+    pub start_hover: &'static str,
+    pub continue_: &'static str,
+    pub pause: &'static str,
+    pub single_step: &'static str,
+    pub single_step_hover: &'static str,
+    pub record: &'static str,
+    pub record_interval: &'static str,
+    pub replay: &'static str,
+    pub replay_hover: &'static str,
+    pub time: &'static str,
+    pub replay_speed: &'static str,
+    pub live: &'static str,
+    pub export_animation: &'static str,
+    pub png_sequence: &'static str,
+    pub export_path_hover: &'static str,
+    pub fps: &'static str,
+    pub frames: &'static str,
+    pub steps_per_frame: &'static str,
+    pub export_frames: &'static str,
+    pub export_frames_hover: &'static str,
+    pub frame_of: &'static str,
+    pub abort: &'static str,
+    pub export_failed: &'static str,
+    pub exported_frames: &'static str,
+    pub export_aborted: &'static str,
+    pub export_svg: &'static str,
+    pub export_svg_hover: &'static str,
+    pub saved: &'static str,
+    pub svg_failed: &'static str,
+    pub presentation: &'static str,
+    pub load: &'static str,
+    pub load_presentation_hover: &'static str,
+    pub loading_failed: &'static str,
+    pub loaded: &'static str,
+    pub scene_of: &'static str,
+    pub end_presentation: &'static str,
+    pub to_linear: &'static str,
+    pub to_log: &'static str,
+    pub speed: &'static str,
+    pub coins: &'static str,
+    pub dice: &'static str,
+    pub random_walk: &'static str,
+    pub faces: &'static str,
+    pub faces_hover: &'static str,
+    pub walk_end: &'static str,
+    pub walk_end_hover: &'static str,
+    pub walk_max: &'static str,
+    pub walk_max_hover: &'static str,
+    pub walk_area: &'static str,
+    pub walk_area_hover: &'static str,
+    pub seed: &'static str,
+    pub step_size: &'static str,
+    pub to_log_f: &'static str,
+    pub to_log_log_f: &'static str,
+    pub zoom: &'static str,
+    pub rescale: &'static str,
+    pub line: &'static str,
+    pub threshold: &'static str,
+    pub initial_log_f: &'static str,
+    pub check_every: &'static str,
+    pub flatness: &'static str,
+    pub all_visited: &'static str,
+    pub min_over_mean: &'static str,
+    pub log_f_reduction: &'static str,
+    pub flat_histogram: &'static str,
+    pub log_f_mode: &'static str,
+    pub wl_steps: &'static str,
+    pub start_entropic: &'static str,
+    pub immediately: &'static str,
+    pub at_log_f: &'static str,
+    pub wl_finished: &'static str,
+    pub manual: &'static str,
+    pub entropic_start_log_f: &'static str,
+    pub branch_entropic: &'static str,
+    pub branch_entropic_hover: &'static str,
+    pub refine_every: &'static str,
+    pub refine_rounds: &'static str,
+    pub hist_lin: &'static str,
+    pub hist_log: &'static str,
+    pub simple_hist: &'static str,
+    pub residuals: &'static str,
+    pub rate_function: &'static str,
+    pub rate_function_hover: &'static str,
+    pub metropolis: &'static str,
+    pub metropolis_hover: &'static str,
+    pub bias_hover: &'static str,
+    pub reweighting: &'static str,
+    pub reweighting_hover: &'static str,
+    pub beta_hover: &'static str,
+    pub metropolis_info: &'static str,
+    pub hist_heatmap: &'static str,
+    pub density_view: &'static str,
+    pub ratio_view: &'static str,
+    pub ratio_view_hover: &'static str,
+    pub ratio_axis: &'static str,
+    pub ratio_error_axis: &'static str,
+    pub noise: &'static str,
+    pub best_pr: &'static str,
+    pub noise_amount: &'static str,
+    pub noise_seed: &'static str,
+    pub limit_to_1: &'static str,
+    pub analytic_results: &'static str,
+    pub wl_results: &'static str,
+    pub entropic_results: &'static str,
+    pub simple_results: &'static str,
+    pub metropolis_results: &'static str,
+    pub best: &'static str,
+    pub residual_axis: &'static str,
+    pub run_time_axis: &'static str,
+    pub entropic_start: &'static str,
+    pub entropic_refinement: &'static str,
+    pub histogram: &'static str,
+    pub hits: &'static str,
+    pub wl_histogram: &'static str,
+    pub entropic_histogram: &'static str,
+    pub simple_histogram: &'static str,
+    pub metropolis_histogram: &'static str,
+    pub configured_in: &'static str,
+    pub reload: &'static str,
+    pub simple: &'static str,
+    pub independent_sample: &'static str,
+    pub proposed_move: &'static str,
+    pub energy_old_new: &'static str,
+    pub acceptance: &'static str,
+    pub random_number: &'static str,
+    pub result: &'static str,
+    pub accepted: &'static str,
+    pub rejected: &'static str,
+    pub density: &'static str,
+    pub log_f_refined: &'static str,
+    pub x_coins: &'static str,
+    pub x_dice: &'static str,
+    pub x_walk_end: &'static str,
+    pub x_walk_max: &'static str,
+    pub x_walk_area: &'static str,
+    pub y_coins: &'static str,
+    pub y_dice: &'static str,
+    pub y_walk_end: &'static str,
+    pub y_walk_max: &'static str,
+    pub y_walk_area: &'static str,
+    pub hide_side_panel: &'static str,
+    pub show_side_panel: &'static str,
+    pub fullscreen: &'static str,
+    pub layout: &'static str,
+    pub layout_hover: &'static str,
+    pub density_plot: &'static str,
+    pub log_f_plot: &'static str,
+    pub histogram_plot: &'static str,
+    pub move_up: &'static str,
+    pub arrangement_split: &'static str,
+    pub arrangement_columns: &'static str,
+    pub arrangement_rows: &'static str,
+    pub split_share: &'static str,
+    pub annotations: &'static str,
+    pub annotation_off: &'static str,
+    pub annotation_text_kind: &'static str,
+    pub annotation_arrow: &'static str,
+    pub annotation_region: &'static str,
+    pub annotation_text_hover: &'static str,
+    pub annotation_click: &'static str,
+    pub annotation_click_second: &'static str,
+    pub remove: &'static str,
+    pub remove_all: &'static str,
+    pub save: &'static str,
+    pub saving_failed: &'static str,
+    pub theme: &'static str,
+    pub theme_light: &'static str,
+    pub theme_dark: &'static str,
+    pub theme_projector: &'static str,
+    pub theme_colorblind: &'static str,
+    pub curve_styles: &'static str,
+    pub curve_styles_hover: &'static str,
+    pub width_hover: &'static str,
+    pub draw_line: &'static str,
+    pub draw_points: &'static str,
+    pub reset_styles: &'static str,
+    pub best_pr_hover: &'static str,
+    pub precision_errors: &'static str,
+    pub error_plot: &'static str,
+    pub error_plot_hover: &'static str,
+    pub error_axis: &'static str,
+    pub steps_axis: &'static str,
+    pub classic_limit: &'static str,
+    pub one_over_t_scaling: &'static str,
+    pub export_timed_out: &'static str,
+    pub step_all: &'static str,
+    pub step_all_hover: &'static str,
+    pub entropic: &'static str,
+    pub limit_reached: &'static str,
+    pub frame_size: &'static str,
+    pub export_keep_visible: &'static str,
+    pub action_start: &'static str,
+    pub action_pause: &'static str,
+    pub action_log_scale: &'static str,
+    pub action_ratio_view: &'static str,
+    pub action_noise: &'static str,
+    pub action_hist_scale: &'static str,
+    pub action_zoom_in: &'static str,
+    pub action_zoom_out: &'static str,
+    pub action_next_scene: &'static str,
+    pub action_previous_scene: &'static str,
+    pub action_side_panel: &'static str,
+    pub action_fullscreen: &'static str,
+    pub action_help: &'static str
+}
+
+pub const EN: Texts = Texts{
+    window_title: "Defense of the PhD thesis of Yannick Feld",
+    language: "Language",
+    shortcuts: "Keyboard shortcuts",
+    shortcuts_hover: "Shows all keyboard shortcuts",
+    start: "Start",
+    start_hover: "Starts the simulation.",
+    continue_: "Continue",
+    pause: "Pause",
+    single_step: "Single step",
+    single_step_hover: "Pauses the simulation and performs exactly one Wang-Landau step",
+    record: "Record",
+    record_interval: "record interval",
+    replay: "Replay",
+    replay_hover: "Pauses the simulation and shows the recording",
+    time: "time",
+    replay_speed: "replay speed",
+    live: "Live",
+    export_animation: "Export animation",
+    png_sequence: "PNG sequence",
+    export_path_hover: "GIF: file, PNG sequence: directory",
+    fps: "fps",
+    frames: "frames",
+    steps_per_frame: "steps per frame",
+    export_frames: "Export frames",
+    export_frames_hover: "Restarts the simulation and stores every frame. The frames are screenshots of the plots scaled to the frame size, so the window has to stay visible",
+    frame_of: "frame {} of {}",
+    abort: "Abort",
+    export_failed: "Export failed: {}",
+    exported_frames: "Exported {} frames",
+    export_aborted: "Export aborted",
+    export_svg: "Export SVG",
+    export_svg_hover: "Stores the density plot as vector graphic",
+    saved: "Saved {}",
+    svg_failed: "SVG export failed: {}",
+    presentation: "Presentation",
+    load: "Load",
+    load_presentation_hover: "Loads the scenes, next with → or Page Down, back with ← or Page Up",
+    loading_failed: "Loading failed: {}",
+    loaded: "Loaded {}",
+    scene_of: "scene {} of {}",
+    end_presentation: "End presentation",
+    to_linear: "Switch to linear Scale",
+    to_log: "Switch to logscale",
+    speed: "Speed",
+    coins: "Coins",
+    dice: "Dice",
+    random_walk: "Random walk",
+    faces: "faces",
+    faces_hover: "Number of faces of each die",
+    walk_end: "End",
+    walk_end_hover: "End position of the random walk",
+    walk_max: "Maximum",
+    walk_max_hover: "Maximal position, exact via the reflection principle",
+    walk_area: "Area",
+    walk_area_hover: "Area under the random walk, exact only for N ≤ 200",
+    seed: "Seed",
+    step_size: "step size",
+    to_log_f: "Show log_f",
+    to_log_log_f: "Show log10(log_f)",
+    zoom: "Zoom",
+    rescale: "Rescale",
+    line: "other lines",
+    threshold: "threshold",
+    initial_log_f: "initial log_f",
+    check_every: "check every",
+    flatness: "Flatness criterion",
+    all_visited: "all visited",
+    min_over_mean: "min/mean",
+    log_f_reduction: "log_f reduction",
+    flat_histogram: "flat histogram",
+    log_f_mode: "log_f: {} ({})",
+    wl_steps: "WL steps: {}",
+    start_entropic: "Start entropic sampling",
+    immediately: "immediately",
+    at_log_f: "at log_f",
+    wl_finished: "WL finished",
+    manual: "manual",
+    entropic_start_log_f: "E start log_f",
+    branch_entropic: "Branch entropic",
+    branch_entropic_hover: "Starts a new entropic sampling from the current Wang-Landau estimate",
+    refine_every: "E refine every",
+    refine_rounds: "E rounds",
+    hist_lin: "Hist Lin",
+    hist_log: "Hist Log",
+    simple_hist: "Simp Hist",
+    residuals: "Residuals",
+    rate_function: "Rate function",
+    rate_function_hover: "Empirical rate function -ln P(k/n) / n",
+    metropolis: "Metropolis",
+    metropolis_hover: "Metropolis Markov chain with weight exp(-βE) for comparison",
+    bias_hover: "Negative values favor heads, i.e., high energies",
+    reweighting: "Reweighting",
+    reweighting_hover: "Canonical distribution g(E) exp(-βE) compared with Metropolis",
+    beta_hover: "Inverse temperature, negative values favor high energies",
+    metropolis_info: "Metropolis: {} steps, acceptance rate {}",
+    hist_heatmap: "Hist heatmap",
+    density_view: "Density view",
+    ratio_view: "Ratio view",
+    ratio_view_hover: "Shows the neighbour ratios g(k+1)/g(k), which determine the acceptance probabilities of Wang-Landau, and their relative errors",
+    ratio_axis: "g(k+1) / g(k)",
+    ratio_error_axis: "relative error of g(k+1) / g(k)",
+    noise: "Noise",
+    best_pr: "Best PR",
+    noise_amount: "noise",
+    noise_seed: "noise seed",
+    limit_to_1: "limit to 1",
+    analytic_results: "analytic Results",
+    wl_results: "WL Results",
+    entropic_results: "Entropic Results",
+    simple_results: "Simple Results",
+    metropolis_results: "Metropolis Results",
+    best: "best",
+    residual_axis: "log10 estimate - log10 exact",
+    run_time_axis: "Run time in seconds",
+    entropic_start: "Entropic start",
+    entropic_refinement: "Entropic refinement",
+    histogram: "histogram",
+    hits: "#hits",
+    wl_histogram: "Wang Landau Histogram",
+    entropic_histogram: "Entropic Histogram",
+    simple_histogram: "Simple Histogram",
+    metropolis_histogram: "Metropolis Histogram",
+    configured_in: "Configured in {}, e.g. `pause = Space, Ctrl+P`",
+    reload: "Reload",
+    simple: "Simple",
+    independent_sample: "independent sample with energy {}",
+    proposed_move: "proposed move",
+    energy_old_new: "energy old → new",
+    acceptance: "acceptance",
+    random_number: "random number",
+    result: "result",
+    accepted: "accepted",
+    rejected: "rejected",
+    density: "density",
+    log_f_refined: "log_f refined",
+    x_coins: "Heads rate",
+    x_dice: "Dice sum rate",
+    x_walk_end: "Walk end rate",
+    x_walk_max: "Walk maximum rate",
+    x_walk_area: "Walk area rate",
+    y_coins: "Probability of heads rate",
+    y_dice: "Probability of dice sum rate",
+    y_walk_end: "Probability of walk end rate",
+    y_walk_max: "Probability of walk maximum rate",
+    y_walk_area: "Probability of walk area rate",
+    hide_side_panel: "Hide the side panel",
+    show_side_panel: "Show the side panel",
+    fullscreen: "Fullscreen",
+    layout: "Plot layout",
+    layout_hover: "Choose which plots are shown and how they are arranged",
+    density_plot: "Density",
+    log_f_plot: "log f",
+    histogram_plot: "Histogram",
+    move_up: "Move to the front",
+    arrangement_split: "Left, the rest stacked on the right",
+    arrangement_columns: "Side by side",
+    arrangement_rows: "Below each other",
+    split_share: "Width of the first plot",
+    annotations: "Annotations",
+    annotation_off: "Off",
+    annotation_text_kind: "Text",
+    annotation_arrow: "Arrow",
+    annotation_region: "Region",
+    annotation_text_hover: "Text of the next annotation, optional for arrows and regions",
+    annotation_click: "Click into the density plot",
+    annotation_click_second: "Click the second point",
+    remove: "Remove",
+    remove_all: "Remove all",
+    save: "Save",
+    saving_failed: "Saving failed: {}",
+    theme: "Theme",
+    theme_light: "Light",
+    theme_dark: "Dark",
+    theme_projector: "Projector (high contrast)",
+    theme_colorblind: "Colorblind-safe",
+    curve_styles: "Curve styles",
+    curve_styles_hover: "Color, width, markers and visibility of each curve",
+    width_hover: "Line width or marker radius",
+    draw_line: "Line",
+    draw_points: "Points",
+    reset_styles: "Reset to the theme",
+    best_pr_hover: "The final log f is 2^-x",
+    precision_errors: "Mean error of log10 g: best {}, WL {}",
+    error_plot: "Error over time",
+    error_plot_hover: "Error of the Wang-Landau estimate over the Markov steps, together with the limit of classic Wang-Landau for the chosen threshold and the 1/t scaling",
+    error_axis: "log10(mean |Δ log10 g|)",
+    steps_axis: "log10(Markov steps)",
+    classic_limit: "limit of classic WL",
+    one_over_t_scaling: "1/t scaling guide (estimated prefactor)",
+    export_timed_out: "Export aborted: no screenshot arrived, the window has to stay visible while exporting",
+    step_all: "E + S",
+    step_all_hover: "A single step also performs one step of the entropic and of the simple sampling",
+    entropic: "Entropic",
+    limit_reached: "1/t reaches the limit",
+    frame_size: "frame size in pixels",
+    export_keep_visible: "Keep the window visible until the export is finished, the frames are screenshots",
+    action_start: "Start the simulation",
+    action_pause: "Pause or continue",
+    action_log_scale: "Switch between log and linear scale",
+    action_ratio_view: "Switch between the density and the ratio view",
+    action_noise: "Show or hide the noise",
+    action_hist_scale: "Switch the histogram scale",
+    action_zoom_in: "Zoom in",
+    action_zoom_out: "Zoom out",
+    action_next_scene: "Next scene of the presentation",
+    action_previous_scene: "Previous scene of the presentation",
+    action_side_panel: "Show or hide the side panel",
+    action_fullscreen: "Switch fullscreen on or off",
+    action_help: "Show or hide this list"
+};
+
+pub const DE: Texts = Texts{
+    window_title: "Verteidigung Doktorarbeit Yannick Feld",
+    language: "Sprache",
+    shortcuts: "Tastenkürzel",
+    shortcuts_hover: "Zeigt alle Tastenkürzel",
+    start: "Start",
+    start_hover: "Startet die Simulation.",
+    continue_: "Fortfahren",
+    pause: "Pausieren",
+    single_step: "Einzelschritt",
+    single_step_hover: "Pausiert die Simulation und führt genau einen Wang-Landau Schritt aus",
+    record: "Aufzeichnen",
+    record_interval: "Aufzeichnungsintervall",
+    replay: "Wiedergabe",
+    replay_hover: "Pausiert die Simulation und zeigt die Aufzeichnung",
+    time: "Zeit",
+    replay_speed: "Wiedergabegeschwindigkeit",
+    live: "Live",
+    export_animation: "Animation exportieren",
+    png_sequence: "PNG-Sequenz",
+    export_path_hover: "GIF: Datei, PNG-Sequenz: Verzeichnis",
+    fps: "fps",
+    frames: "Bilder",
+    steps_per_frame: "Schritte pro Bild",
+    export_frames: "Bilder exportieren",
+    export_frames_hover: "Startet die Simulation neu und speichert jedes Bild. Die Bilder sind auf die Bildgröße skalierte Bildschirmfotos der Plots, das Fenster muss also sichtbar bleiben",
+    frame_of: "Bild {} von {}",
+    abort: "Abbrechen",
+    export_failed: "Export fehlgeschlagen: {}",
+    exported_frames: "{} Bilder exportiert",
+    export_aborted: "Export abgebrochen",
+    export_svg: "SVG exportieren",
+    export_svg_hover: "Speichert den Dichte-Plot als Vektorgrafik",
+    saved: "{} gespeichert",
+    svg_failed: "SVG-Export fehlgeschlagen: {}",
+    presentation: "Präsentation",
+    load: "Laden",
+    load_presentation_hover: "Lädt die Szenen, weiter mit → oder Bild ab, zurück mit ← oder Bild auf",
+    loading_failed: "Laden fehlgeschlagen: {}",
+    loaded: "{} geladen",
+    scene_of: "Szene {} von {}",
+    end_presentation: "Präsentation beenden",
+    to_linear: "Wechsel zur linearen Skala",
+    to_log: "Wechsel zur logarithmischen Skala",
+    speed: "Geschwindigkeit",
+    coins: "Münzen",
+    dice: "Würfel",
+    random_walk: "Random Walk",
+    faces: "Seiten",
+    faces_hover: "Anzahl der Seiten jedes Würfels",
+    walk_end: "Ende",
+    walk_end_hover: "Endposition des Random Walks",
+    walk_max: "Maximum",
+    walk_max_hover: "Maximale Position, exakt über das Spiegelungsprinzip",
+    walk_area: "Fläche",
+    walk_area_hover: "Fläche unter dem Random Walk, exakt nur für N ≤ 200",
+    seed: "Seed",
+    step_size: "Schrittweite",
+    to_log_f: "log_f zeigen",
+    to_log_log_f: "log10(log_f) zeigen",
+    zoom: "Zoom",
+    rescale: "Neu skalieren",
+    line: "andere Linien",
+    threshold: "Schwelle",
+    initial_log_f: "anfängliches log_f",
+    check_every: "prüfe alle",
+    flatness: "Flachheitskriterium",
+    all_visited: "alle besucht",
+    min_over_mean: "min/Mittelwert",
+    log_f_reduction: "Verkleinerung von log_f",
+    flat_histogram: "flaches Histogramm",
+    log_f_mode: "log_f: {} ({})",
+    wl_steps: "WL-Schritte: {}",
+    start_entropic: "Start des Entropic Sampling",
+    immediately: "sofort",
+    at_log_f: "bei log_f",
+    wl_finished: "WL fertig",
+    manual: "manuell",
+    entropic_start_log_f: "E Start log_f",
+    branch_entropic: "Entropic abzweigen",
+    branch_entropic_hover: "Startet ein neues Entropic Sampling von der aktuellen Wang-Landau Schätzung",
+    refine_every: "E verfeinern alle",
+    refine_rounds: "E Runden",
+    hist_lin: "Hist linear",
+    hist_log: "Hist log",
+    simple_hist: "Einfach Hist",
+    residuals: "Residuen",
+    rate_function: "Ratenfunktion",
+    rate_function_hover: "Empirische Ratenfunktion -ln P(k/n) / n",
+    metropolis: "Metropolis",
+    metropolis_hover: "Metropolis Markov-Kette mit Gewicht exp(-βE) als Vergleich",
+    bias_hover: "Negative Werte bevorzugen Kopf, also hohe Energien",
+    reweighting: "Umgewichtung",
+    reweighting_hover: "Kanonische Verteilung g(E) exp(-βE) im Vergleich mit Metropolis",
+    beta_hover: "Inverse Temperatur, negative Werte bevorzugen hohe Energien",
+    metropolis_info: "Metropolis: {} Schritte, Akzeptanzrate {}",
+    hist_heatmap: "Hist Heatmap",
+    density_view: "Dichte",
+    ratio_view: "Verhältnisse",
+    ratio_view_hover: "Zeigt die Verhältnisse g(k+1)/g(k) benachbarter Werte, von denen die Akzeptanzwahrscheinlichkeiten von Wang-Landau abhängen, und ihre relativen Fehler",
+    ratio_axis: "g(k+1) / g(k)",
+    ratio_error_axis: "relativer Fehler von g(k+1) / g(k)",
+    noise: "Rauschen",
+    best_pr: "Beste PR",
+    noise_amount: "Rauschen",
+    noise_seed: "Rausch-Seed",
+    limit_to_1: "auf 1 begrenzen",
+    analytic_results: "Analytisches Ergebnis",
+    wl_results: "WL Ergebnis",
+    entropic_results: "Entropic Ergebnis",
+    simple_results: "Einfaches Sampling",
+    metropolis_results: "Metropolis Ergebnis",
+    best: "beste",
+    residual_axis: "log10 Schätzung - log10 exakt",
+    run_time_axis: "Laufzeit in Sekunden",
+    entropic_start: "Start Entropic",
+    entropic_refinement: "Entropic Verfeinerung",
+    histogram: "Histogramm",
+    hits: "#Treffer",
+    wl_histogram: "Wang-Landau Histogramm",
+    entropic_histogram: "Entropic Histogramm",
+    simple_histogram: "Histogramm einfaches Sampling",
+    metropolis_histogram: "Metropolis Histogramm",
+    configured_in: "Einstellbar in {}, z.B. `pause = Space, Ctrl+P`",
+    reload: "Neu laden",
+    simple: "Einfach",
+    independent_sample: "unabhängige Stichprobe mit Energie {}",
+    proposed_move: "vorgeschlagener Zug",
+    energy_old_new: "Energie alt → neu",
+    acceptance: "Akzeptanz",
+    random_number: "Zufallszahl",
+    result: "Ergebnis",
+    accepted: "angenommen",
+    rejected: "abgelehnt",
+    density: "Dichte",
+    log_f_refined: "log_f verkleinert",
+    x_coins: "Kopfrate",
+    x_dice: "Rate der Augensumme",
+    x_walk_end: "Rate der Endposition",
+    x_walk_max: "Rate des Maximums",
+    x_walk_area: "Rate der Fläche",
+    y_coins: "Wahrscheinlichkeit der Kopfrate",
+    y_dice: "Wahrscheinlichkeit der Augensumme",
+    y_walk_end: "Wahrscheinlichkeit der Endposition",
+    y_walk_max: "Wahrscheinlichkeit des Maximums",
+    y_walk_area: "Wahrscheinlichkeit der Fläche",
+    hide_side_panel: "Seitenleiste verbergen",
+    show_side_panel: "Seitenleiste zeigen",
+    fullscreen: "Vollbild",
+    layout: "Anordnung der Plots",
+    layout_hover: "Auswählen, welche Plots wie angeordnet gezeigt werden",
+    density_plot: "Dichte",
+    log_f_plot: "log f",
+    histogram_plot: "Histogramm",
+    move_up: "Nach vorne schieben",
+    arrangement_split: "Links, der Rest rechts gestapelt",
+    arrangement_columns: "Nebeneinander",
+    arrangement_rows: "Untereinander",
+    split_share: "Breite des ersten Plots",
+    annotations: "Anmerkungen",
+    annotation_off: "Aus",
+    annotation_text_kind: "Text",
+    annotation_arrow: "Pfeil",
+    annotation_region: "Bereich",
+    annotation_text_hover: "Text der nächsten Anmerkung, für Pfeile und Bereiche optional",
+    annotation_click: "In den Dichte-Plot klicken",
+    annotation_click_second: "Den zweiten Punkt anklicken",
+    remove: "Entfernen",
+    remove_all: "Alle entfernen",
+    save: "Speichern",
+    saving_failed: "Speichern fehlgeschlagen: {}",
+    theme: "Farbschema",
+    theme_light: "Hell",
+    theme_dark: "Dunkel",
+    theme_projector: "Beamer (hoher Kontrast)",
+    theme_colorblind: "Farbenblind-freundlich",
+    curve_styles: "Kurvenstile",
+    curve_styles_hover: "Farbe, Breite, Marker und Sichtbarkeit jeder Kurve",
+    width_hover: "Linienbreite oder Markerradius",
+    draw_line: "Linie",
+    draw_points: "Punkte",
+    reset_styles: "Auf das Farbschema zurücksetzen",
+    best_pr_hover: "Das letzte log f ist 2^-x",
+    precision_errors: "Mittlerer Fehler von log10 g: beste {}, WL {}",
+    error_plot: "Fehler über die Zeit",
+    error_plot_hover: "Fehler der Wang-Landau-Schätzung über die Markov-Schritte, zusammen mit der Grenze des klassischen Wang-Landau für die gewählte Schwelle und dem 1/t-Verlauf",
+    error_axis: "log10(mittleres |Δ log10 g|)",
+    steps_axis: "log10(Markov-Schritte)",
+    classic_limit: "Grenze von klassischem WL",
+    one_over_t_scaling: "1/t-Skalierung als Orientierung (geschätzter Vorfaktor)",
+    export_timed_out: "Export abgebrochen: kein Bildschirmfoto erhalten, das Fenster muss während des Exports sichtbar bleiben",
+    step_all: "E + S",
+    step_all_hover: "Ein Einzelschritt führt auch einen Schritt des Entropic Sampling und des einfachen Samplings aus",
+    entropic: "Entropic Sampling",
+    limit_reached: "1/t erreicht die Grenze",
+    frame_size: "Bildgröße in Pixeln",
+    export_keep_visible: "Das Fenster muss bis zum Ende des Exports sichtbar bleiben, die Bilder sind Bildschirmfotos",
+    action_start: "Simulation starten",
+    action_pause: "Pausieren oder fortfahren",
+    action_log_scale: "Zwischen logarithmischer und linearer Skala wechseln",
+    action_ratio_view: "Zwischen Dichte und Verhältnissen wechseln",
+    action_noise: "Rauschen zeigen oder verbergen",
+    action_hist_scale: "Skala des Histogramms wechseln",
+    action_zoom_in: "Vergrößern",
+    action_zoom_out: "Verkleinern",
+    action_next_scene: "Nächste Szene der Präsentation",
+    action_previous_scene: "Vorherige Szene der Präsentation",
+    action_side_panel: "Seitenleiste zeigen oder verbergen",
+    action_fullscreen: "Vollbild an- oder ausschalten",
+    action_help: "Diese Liste zeigen oder verbergen"
+};
@@ -0,0 +1,104 @@
+use egui::{Rect, pos2, vec2};
+
+/// The large plots of the central panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlotPanel{
+    Density,
+    LogF,
+    Histogram
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Arrangement{
+    /// The first plot on the left, all others stacked on the right
+    Split,
+    /// All plots next to each other
+    Columns,
+    /// All plots below each other
+    Rows
+}
+
+/// Which plots are shown in the central panel and where
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlotLayout{
+    /// Plots in the order in which they are placed, with their visibility
+    pub panels: [(PlotPanel, bool); 3],
+    pub arrangement: Arrangement,
+    /// Share of the width that the first plot gets in the `Split` arrangement
+    pub split: f32
+}
+
+impl Default for PlotLayout{
+    fn default() -> Self {
+        Self{
+            panels: [
+                (PlotPanel::Density, true),
+                (PlotPanel::LogF, true),
+                (PlotPanel::Histogram, true)
+            ],
+            arrangement: Arrangement::Split,
+            split: 0.5
+        }
+    }
+}
+
+impl PlotLayout{
+    /// Swaps the plot at `index` with the one before it
+    pub fn move_up(&mut self, index: usize)
+    {
+        if index > 0 {
+            self.panels.swap(index - 1, index);
+        }
+    }
+
+    /// Where the visible plots go inside of `rect`. `gap` is the space between two plots
+    pub fn rects(&self, rect: Rect, gap: f32) -> Vec<(PlotPanel, Rect)>
+    {
+        let visible: Vec<PlotPanel> = self.panels
+            .iter()
+            .filter(|(_, visible)| *visible)
+            .map(|(panel, _)| *panel)
+            .collect();
+        let rects = match (self.arrangement, visible.len()) {
+            (_, 0) => Vec::new(),
+            (Arrangement::Split, count) if count > 1 => {
+                let width = (rect.width() - gap) * self.split;
+                let left = Rect::from_min_size(rect.min, vec2(width, rect.height()));
+                let right = Rect::from_min_max(pos2(left.max.x + gap, rect.min.y), rect.max);
+                let mut rects = vec![left];
+                rects.extend(divide(right, count - 1, gap, true));
+                rects
+            },
+            (Arrangement::Rows, count) => divide(rect, count, gap, true),
+            (_, count) => divide(rect, count, gap, false)
+        };
+        visible.into_iter()
+            .zip(rects)
+            .collect()
+    }
+}
+
+/// Splits `rect` into `count` equally large parts, below each other if `vertical`
+fn divide(rect: Rect, count: usize, gap: f32, vertical: bool) -> Vec<Rect>
+{
+    let total = if vertical { rect.height() } else { rect.width() };
+    let size = (total - gap * (count - 1) as f32) / count as f32;
+    (0..count)
+        .map(
+            |i|
+            {
+                let offset = i as f32 * (size + gap);
+                if vertical {
+                    Rect::from_min_size(
+                        pos2(rect.min.x, rect.min.y + offset),
+                        vec2(rect.width(), size)
+                    )
+                } else {
+                    Rect::from_min_size(
+                        pos2(rect.min.x + offset, rect.min.y),
+                        vec2(size, rect.height())
+                    )
+                }
+            }
+        ).collect()
+}
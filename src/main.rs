@@ -4,13 +4,53 @@ mod app;
 use app::*;
 mod coins;
 pub use coins::*;
+mod model;
+pub use model::*;
+mod wang_landau;
+pub use wang_landau::*;
+mod recording;
+pub use recording::*;
+mod export;
+pub use export::*;
+mod svg;
+pub use svg::*;
+mod heatmap;
+pub use heatmap::*;
+mod dice;
+pub use dice::*;
+mod walk;
+pub use walk::*;
+mod metropolis;
+pub use metropolis::*;
+mod canonical;
+pub use canonical::*;
+mod presentation;
+pub use presentation::*;
+mod shortcuts;
+pub use shortcuts::*;
+mod i18n;
+pub use i18n::*;
+mod layout;
+pub use layout::*;
+mod annotation;
+pub use annotation::*;
+mod theme;
+pub use theme::*;
+mod style;
+pub use style::*;
+mod precision;
+mod ratio;
+pub use precision::*;
+pub use ratio::*;
+mod exact;
+pub use exact::*;
 
 fn main() -> eframe::Result<()> {
     // Log to stdout (if you run with `RUST_LOG=debug`).
 
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
-        "Verteidigung Doktorarbeit Yannick Feld",
+        Lang::default().texts().window_title,
         native_options,
         Box::new(|cc| Box::new(AppState::new(cc))),
     )
@@ -0,0 +1,76 @@
+use rand::Rng;
+use rand_pcg::Pcg64;
+use sampling::*;
+use crate::Model;
+
+/// Markov chain Monte Carlo at fixed inverse temperature `beta`,
+/// i.e., the states are sampled with weight `exp(-beta E)`
+#[derive(Clone)]
+pub struct Metropolis<M: Model>{
+    ensemble: M,
+    rng: Pcg64,
+    beta: f64,
+    hist: HistUsizeFast,
+    step_count: usize,
+    accepted: usize
+}
+
+impl<M: Model> Metropolis<M>{
+    pub fn new(ensemble: M, rng: Pcg64, beta: f64) -> Self
+    {
+        let hist = HistUsizeFast::new_inclusive(0, ensemble.num_bins() - 1)
+            .unwrap();
+        Self{
+            ensemble,
+            rng,
+            beta,
+            hist,
+            step_count: 0,
+            accepted: 0
+        }
+    }
+
+    pub fn beta(&self) -> f64
+    {
+        self.beta
+    }
+
+    pub fn step_counter(&self) -> usize
+    {
+        self.step_count
+    }
+
+    pub fn acceptance_rate(&self) -> f64
+    {
+        self.accepted as f64 / self.step_count.max(1) as f64
+    }
+
+    /// Energies of all states of the chain so far
+    pub fn hist(&self) -> &HistUsizeFast
+    {
+        &self.hist
+    }
+
+    pub fn metropolis_while<F>(&mut self, mut cond: F)
+    where F: FnMut() -> bool
+    {
+        while cond() {
+            self.metropolis_step();
+        }
+    }
+
+    pub fn metropolis_step(&mut self)
+    {
+        let old_energy = self.ensemble.energy();
+        let m = self.ensemble.propose(&mut self.rng);
+        let delta = self.ensemble.energy() as f64 - old_energy as f64;
+        let acceptance = (-self.beta * delta).exp();
+        if acceptance >= 1.0 || self.rng.gen::<f64>() < acceptance {
+            self.accepted += 1;
+        } else {
+            self.ensemble.undo(&m);
+        }
+        self.step_count += 1;
+        self.hist.increment_quiet(self.ensemble.energy());
+    }
+}
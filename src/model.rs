@@ -0,0 +1,264 @@
+use rand_pcg::Pcg64;
+use crate::{Texts, CoinFlipSequence, CoinFlipMove, Dice, DiceMove, RandomWalk, WalkMove, Observable, MAX_AREA_STEPS, MAX_EXACT_BINS};
+
+/// Largest system size that can be selected
+pub const MAX_N: usize = 10000;
+
+/// A system that can be sampled by the Wang-Landau and entropic samplers.
+///
+/// The energy of a state is already the bin index, i.e.,
+/// it has to be in `0..self.num_bins()`
+pub trait Model: Clone + Send + Sync + 'static
+{
+    /// Everything that is needed to undo a move
+    type Move: Clone + std::fmt::Debug + Send + Sync;
+
+    /// Current energy, i.e., bin index
+    fn energy(&self) -> usize;
+
+    /// Number of possible energies
+    fn num_bins(&self) -> usize;
+
+    /// Perform a random move and return the information needed to undo it
+    fn propose(&mut self, rng: &mut Pcg64) -> Self::Move;
+
+    /// Undo a move that was returned by `propose`
+    fn undo(&mut self, m: &Self::Move);
+
+    /// Natural logarithm of the exact number of states of each bin,
+    /// see the `exact` module. `None` if no exact result is available
+    fn exact_log_density(&self) -> Option<Vec<f64>>
+    {
+        None
+    }
+
+    /// Energy of an independently drawn random state, used for simple sampling.
+    /// Does not change the current state
+    fn sample_energy(&self, rng: &mut Pcg64) -> usize;
+
+    /// Perform `count` random moves. The moves are stored in `moves`
+    fn propose_steps(&mut self, count: usize, rng: &mut Pcg64, moves: &mut Vec<Self::Move>)
+    {
+        moves.clear();
+        moves.extend(
+            (0..count).map(|_| self.propose(rng))
+        );
+    }
+
+    /// Undo all moves, last move first
+    fn undo_steps(&mut self, moves: &[Self::Move])
+    {
+        moves.iter()
+            .rev()
+            .for_each(|m| self.undo(m));
+    }
+}
+
+/// The systems that can be selected in the app
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModelKind{
+    Coins,
+    Dice{faces: usize},
+    Walk{observable: Observable}
+}
+
+impl ModelKind{
+    pub fn x_label(&self, t: &'static Texts) -> &'static str
+    {
+        match self {
+            ModelKind::Coins => t.x_coins,
+            ModelKind::Dice{..} => t.x_dice,
+            ModelKind::Walk{observable: Observable::End} => t.x_walk_end,
+            ModelKind::Walk{observable: Observable::Max} => t.x_walk_max,
+            ModelKind::Walk{observable: Observable::Area} => t.x_walk_area
+        }
+    }
+
+    pub fn y_label(&self, t: &'static Texts) -> &'static str
+    {
+        match self {
+            ModelKind::Coins => t.y_coins,
+            ModelKind::Dice{..} => t.y_dice,
+            ModelKind::Walk{observable: Observable::End} => t.y_walk_end,
+            ModelKind::Walk{observable: Observable::Max} => t.y_walk_max,
+            ModelKind::Walk{observable: Observable::Area} => t.y_walk_area
+        }
+    }
+
+    /// Largest system size, the number of bins of the walk area grows quadratically
+    pub fn max_n(&self) -> usize
+    {
+        match self {
+            ModelKind::Walk{observable: Observable::Area} => MAX_AREA_STEPS,
+            _ => MAX_N
+        }
+    }
+
+    /// Exact large deviation rate function at the rate `x`, if it is the one of fair coins,
+    /// i.e., ln 2 + x ln x + (1-x) ln(1-x)
+    pub fn rate_function(&self, x: f64) -> Option<f64>
+    {
+        match self {
+            ModelKind::Coins | ModelKind::Walk{observable: Observable::End} => {
+                let entropy = |p: f64| if p > 0.0 { p * p.ln() } else { 0.0 };
+                Some(std::f64::consts::LN_2 + entropy(x) + entropy(1.0 - x))
+            },
+            _ => None
+        }
+    }
+
+    /// Analytic neighbour ratio g(k+1)/g(k) for a system of size `n`, if it is known.
+    /// `k` has to be below the last bin
+    pub fn neighbour_ratio(&self, n: usize, k: usize) -> Option<f64>
+    {
+        match self {
+            ModelKind::Coins | ModelKind::Walk{observable: Observable::End} if k < n => {
+                Some((n - k) as f64 / (k + 1) as f64)
+            },
+            _ => None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum EnsembleMove{
+    Coin(CoinFlipMove),
+    Dice(DiceMove),
+    Walk(WalkMove)
+}
+
+/// One of the selectable systems, so that the samplers 
+/// do not need to be generic in the app
+#[derive(Clone)]
+pub enum Ensemble{
+    Coins(CoinFlipSequence),
+    Dice(Dice),
+    Walk(RandomWalk)
+}
+
+impl Ensemble{
+    /// System of size `n`, i.e., `n` coins, `n` dice or `n` steps
+    pub fn new(kind: ModelKind, n: usize, rng: &mut Pcg64) -> Self
+    {
+        match kind {
+            ModelKind::Coins => Ensemble::Coins(CoinFlipSequence::new(n, rng)),
+            ModelKind::Dice{faces} => Ensemble::Dice(Dice::new(n, faces, rng)),
+            ModelKind::Walk{observable} => Ensemble::Walk(RandomWalk::new(n, observable, rng))
+        }
+    }
+}
+
+impl Model for Ensemble{
+    type Move = EnsembleMove;
+
+    fn energy(&self) -> usize
+    {
+        match self {
+            Ensemble::Coins(c) => c.energy(),
+            Ensemble::Dice(d) => d.energy(),
+            Ensemble::Walk(w) => w.energy()
+        }
+    }
+
+    fn num_bins(&self) -> usize
+    {
+        match self {
+            Ensemble::Coins(c) => c.num_bins(),
+            Ensemble::Dice(d) => d.num_bins(),
+            Ensemble::Walk(w) => w.num_bins()
+        }
+    }
+
+    fn propose(&mut self, rng: &mut Pcg64) -> EnsembleMove
+    {
+        match self {
+            Ensemble::Coins(c) => EnsembleMove::Coin(c.propose(rng)),
+            Ensemble::Dice(d) => EnsembleMove::Dice(d.propose(rng)),
+            Ensemble::Walk(w) => EnsembleMove::Walk(w.propose(rng))
+        }
+    }
+
+    fn undo(&mut self, m: &EnsembleMove)
+    {
+        match (self, m) {
+            (Ensemble::Coins(c), EnsembleMove::Coin(m)) => c.undo(m),
+            (Ensemble::Dice(d), EnsembleMove::Dice(m)) => d.undo(m),
+            (Ensemble::Walk(w), EnsembleMove::Walk(m)) => w.undo(m),
+            _ => unreachable!("Move does not belong to the ensemble")
+        }
+    }
+
+    /// The same limit of the bins applies to every model
+    fn exact_log_density(&self) -> Option<Vec<f64>>
+    {
+        if self.num_bins() > MAX_EXACT_BINS {
+            return None;
+        }
+        match self {
+            Ensemble::Coins(c) => c.exact_log_density(),
+            Ensemble::Dice(d) => d.exact_log_density(),
+            Ensemble::Walk(w) => w.exact_log_density()
+        }
+    }
+
+    fn sample_energy(&self, rng: &mut Pcg64) -> usize
+    {
+        match self {
+            Ensemble::Coins(c) => c.sample_energy(rng),
+            Ensemble::Dice(d) => d.sample_energy(rng),
+            Ensemble::Walk(w) => w.sample_energy(rng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn undo_steps_restores_energy()
+    {
+        let kinds = [
+            ModelKind::Coins,
+            ModelKind::Dice{faces: 6},
+            ModelKind::Walk{observable: Observable::End},
+            ModelKind::Walk{observable: Observable::Max},
+            ModelKind::Walk{observable: Observable::Area}
+        ];
+        let mut rng = Pcg64::seed_from_u64(9823);
+        let mut moves = Vec::new();
+        for kind in kinds {
+            let mut ensemble = Ensemble::new(kind, 30, &mut rng);
+            for _ in 0..200 {
+                let energy = ensemble.energy();
+                ensemble.propose_steps(5, &mut rng, &mut moves);
+                assert_eq!(moves.len(), 5);
+                ensemble.undo_steps(&moves);
+                assert_eq!(ensemble.energy(), energy, "{kind:?}");
+                // move on, so that different states are tested
+                ensemble.propose_steps(1, &mut rng, &mut moves);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_density_is_limited()
+    {
+        let mut rng = Pcg64::seed_from_u64(3);
+        let cases = [
+            (ModelKind::Coins, MAX_EXACT_BINS - 1, true),
+            (ModelKind::Coins, MAX_EXACT_BINS, false),
+            (ModelKind::Dice{faces: 3}, MAX_EXACT_BINS / 2, false),
+            (ModelKind::Walk{observable: Observable::Max}, MAX_EXACT_BINS, false),
+            (ModelKind::Walk{observable: Observable::Area}, 260, false)
+        ];
+        for (kind, n, exact) in cases {
+            let ensemble = Ensemble::new(kind, n, &mut rng);
+            assert_eq!(ensemble.exact_log_density().is_some(), exact, "{kind:?}");
+        }
+        // the largest walk is within the limit
+        let walk = Ensemble::new(ModelKind::Walk{observable: Observable::Area}, MAX_AREA_STEPS, &mut rng);
+        assert!(walk.num_bins() <= MAX_EXACT_BINS);
+    }
+}
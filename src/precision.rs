@@ -0,0 +1,314 @@
+//! Best estimate of a density of states that is possible with a finite precision of log f.
+//!
+//! Wang-Landau changes ln g(E) only in multiples of the modification factor ln f.
+//! With a final ln f = 2^-f_steps, the difference of ln g between two neighbouring
+//! energies is therefore only known as a multiple of ln f. [`PrecisionLimit`] rounds
+//! the differences of an exact density to such multiples, optionally disturbs them
+//! with noise and integrates them again. The deviation of the result from the exact
+//! density, see [`log10_error`], is the error that remains even after a perfect run
+//! and can be compared to the error of the actual Wang-Landau estimate.
+//!
+//! The statistical error of an estimate refined with ln f is about sqrt(ln f), see
+//! [`expected_error`]. For classic Wang-Landau this error saturates at the final ln f,
+//! while the 1/t algorithm keeps improving with 1/sqrt(t).
+
+use std::f64::consts::{LN_10, PI};
+use rand::SeedableRng;
+use rand::distributions::{Distribution, Uniform};
+use rand_pcg::Pcg64;
+use sampling::norm_log10_sum_to_1;
+
+/// Differences of ln g larger than this are not resolved
+const MAX_LN_RATIO: f64 = 8.0;
+
+/// Model of the finite precision of the Wang-Landau estimate
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PrecisionLimit{
+    /// ln f = 2^-f_steps
+    pub f_steps: i32,
+    /// Largest number of ln f steps that the noise moves from one difference to the next,
+    /// i.e., by how much ln g of a single bin can be off
+    pub noise: i32,
+    pub noise_seed: u64,
+    /// Noise can not make a neighbour ratio larger than 1, i.e., it never reverses a slope
+    pub limit_to_1: bool
+}
+
+impl Default for PrecisionLimit{
+    fn default() -> Self {
+        Self{
+            f_steps: 0,
+            noise: 0,
+            noise_seed: 1238947,
+            limit_to_1: false
+        }
+    }
+}
+
+impl PrecisionLimit{
+    pub fn log_f(&self) -> f64
+    {
+        2.0_f64.powi(-self.f_steps)
+    }
+
+    /// Absolute differences of ln g between neighbouring bins of `log10_density`,
+    /// in units of ln f and including the noise
+    pub fn steps(&self, log10_density: &[f64]) -> Vec<i64>
+    {
+        let log_f = self.log_f();
+        let max = (MAX_LN_RATIO / log_f).round() as i64;
+        let mut steps: Vec<i64> = log10_density.windows(2)
+            .map(
+                |pair|
+                {
+                    let diff = (pair[1] - pair[0]).abs() * LN_10;
+                    ((diff / log_f).round() as i64).min(max)
+                }
+            ).collect();
+        self.add_noise(&mut steps);
+        steps
+    }
+
+    /// The noise is more likely in the tails, which Wang-Landau reaches last
+    fn add_noise(&self, steps: &mut [i64])
+    {
+        if self.noise <= 0 {
+            return;
+        }
+        let mut rng = Pcg64::seed_from_u64(self.noise_seed);
+        let uni = Uniform::new(0.0, 1.0);
+        let amount = Uniform::new_inclusive(0, self.noise);
+        let last = steps.len().saturating_sub(1);
+        for i in 1..last {
+            let p = (0.5 - i as f64 / last as f64).abs() * 2.0 + 0.075;
+            if uni.sample(&mut rng) < p * p {
+                let v = amount.sample(&mut rng) as i64;
+                steps[i - 1] += v;
+                steps[i] -= v;
+            }
+        }
+    }
+
+    /// Ratios of the smaller to the larger of two neighbouring g.
+    /// Without `limit_to_1` the noise can make them larger than 1
+    pub fn ratios(&self, log10_density: &[f64]) -> Vec<f64>
+    {
+        let log_f = self.log_f();
+        self.steps(log10_density)
+            .into_iter()
+            .map(
+                |steps|
+                {
+                    let ln_ratio = -(steps as f64) * log_f;
+                    if self.limit_to_1 {
+                        ln_ratio.min(0.0).exp()
+                    } else {
+                        ln_ratio.exp()
+                    }
+                }
+            ).collect()
+    }
+
+    /// The best estimate of `log10_density`. It is normalized, i.e., the probabilities sum to 1
+    pub fn log10_density(&self, log10_density: &[f64]) -> Vec<f64>
+    {
+        if log10_density.is_empty() {
+            return Vec::new();
+        }
+        let ratios = self.ratios(log10_density);
+        let mut estimate = Vec::with_capacity(log10_density.len());
+        let mut current = 0.0;
+        estimate.push(current);
+        // the ratios only know how large a step is, the direction is taken from the exact density
+        for (pair, ratio) in log10_density.windows(2).zip(ratios) {
+            if pair[1] >= pair[0] {
+                current -= ratio.log10();
+            } else {
+                current += ratio.log10();
+            }
+            estimate.push(current);
+        }
+        norm_log10_sum_to_1(&mut estimate);
+        estimate
+    }
+
+    /// Error of the best estimate of `log10_density`, see [`log10_error`]
+    pub fn predicted_error(&self, log10_density: &[f64]) -> Option<f64>
+    {
+        log10_error(&self.log10_density(log10_density), log10_density)
+    }
+}
+
+/// Mean absolute difference of two normalized log10 densities.
+/// Bins in which one of them is not finite are skipped, None if no bin is left
+pub fn log10_error(estimate: &[f64], exact: &[f64]) -> Option<f64>
+{
+    let (sum, count) = estimate.iter()
+        .zip(exact)
+        .filter(|(est, ex)| est.is_finite() && ex.is_finite())
+        .fold((0.0, 0_usize), |(sum, count), (est, ex)| (sum + (est - ex).abs(), count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Expected error, see [`log10_error`], of a Wang-Landau estimate that is refined with `log_f`.
+/// ln g of a bin fluctuates by about sqrt(ln f) (Zhou and Bhatt, 2005). For Gaussian
+/// fluctuations the mean absolute deviation is sqrt(2/π) times that
+pub fn expected_error(log_f: f64) -> f64
+{
+    (2.0 / PI * log_f).sqrt() / LN_10
+}
+
+/// Classic Wang-Landau stops refining once ln f drops below `threshold`,
+/// so its error saturates at this value however long it runs
+pub fn saturation_error(threshold: f64) -> f64
+{
+    expected_error(threshold)
+}
+
+/// Expected error of the 1/t algorithm after `steps` Markov steps, where ln f = bins / t.
+/// Only the scaling with the steps is reliable: the prefactor assumes that the error
+/// is the fluctuation of a single refinement with the current ln f, so the curve is a guide
+pub fn one_over_t_error(bins: usize, steps: f64) -> f64
+{
+    expected_error(bins as f64 / steps)
+}
+
+/// Number of steps after which the 1/t algorithm reaches the saturation error of classic
+/// Wang-Landau with `threshold`
+pub fn saturation_steps(bins: usize, threshold: f64) -> f64
+{
+    bins as f64 / threshold
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Normalized log10 of the binomial distribution
+    fn coins(n: usize) -> Vec<f64>
+    {
+        let mut density = vec![0.0];
+        for k in 1..=n {
+            let last = density[k - 1];
+            density.push(last + ((n - k + 1) as f64 / k as f64).log10());
+        }
+        norm_log10_sum_to_1(&mut density);
+        density
+    }
+
+    fn from_ln(ln_density: &[f64]) -> Vec<f64>
+    {
+        ln_density.iter()
+            .map(|ln| ln / LN_10)
+            .collect()
+    }
+
+    #[test]
+    fn steps_are_rounded_differences()
+    {
+        let limit = PrecisionLimit { f_steps: 1, ..Default::default() };
+        let density = from_ln(&[0.0, 1.0, 1.3, 0.2]);
+        assert_eq!(limit.steps(&density), vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn steps_are_capped()
+    {
+        let limit = PrecisionLimit { f_steps: 2, ..Default::default() };
+        let density = from_ln(&[0.0, 20.0, 19.0]);
+        assert_eq!(limit.steps(&density), vec![32, 4]);
+    }
+
+    #[test]
+    fn fine_precision_reproduces_density()
+    {
+        let limit = PrecisionLimit { f_steps: 20, ..Default::default() };
+        let exact = coins(100);
+        let error = limit.predicted_error(&exact).unwrap();
+        assert!(error < 1e-4, "error {error}");
+
+        // also for a density that only decreases
+        let mut exact = from_ln(&(0..50).map(|i| -0.7 * i as f64).collect::<Vec<_>>());
+        norm_log10_sum_to_1(&mut exact);
+        let error = limit.predicted_error(&exact).unwrap();
+        assert!(error < 1e-4, "error {error}");
+    }
+
+    #[test]
+    fn coarse_precision_increases_error()
+    {
+        let exact = coins(200);
+        let errors: Vec<f64> = [0, 3, 6]
+            .iter()
+            .map(|f_steps| PrecisionLimit { f_steps: *f_steps, ..Default::default() })
+            .map(|limit| limit.predicted_error(&exact).unwrap())
+            .collect();
+        assert!(errors[0] > errors[1], "{errors:?}");
+        assert!(errors[1] > errors[2], "{errors:?}");
+    }
+
+    #[test]
+    fn estimate_is_normalized()
+    {
+        let limit = PrecisionLimit { f_steps: 2, noise: 3, ..Default::default() };
+        let estimate = limit.log10_density(&coins(300));
+        let sum: f64 = estimate.iter().map(|v| 10_f64.powf(*v)).sum();
+        assert!((sum - 1.0).abs() < 1e-10, "sum {sum}");
+        assert!(limit.log10_density(&[]).is_empty());
+    }
+
+    #[test]
+    fn noise_is_reproducible_and_keeps_total()
+    {
+        let exact = coins(100);
+        let clean = PrecisionLimit { f_steps: 3, ..Default::default() };
+        let noisy = PrecisionLimit { noise: 5, ..clean };
+        let steps = noisy.steps(&exact);
+        assert_eq!(steps, noisy.steps(&exact));
+        assert_ne!(steps, clean.steps(&exact));
+        assert_eq!(steps.iter().sum::<i64>(), clean.steps(&exact).iter().sum::<i64>());
+
+        let other_seed = PrecisionLimit { noise_seed: 7, ..noisy };
+        assert_ne!(steps, other_seed.steps(&exact));
+    }
+
+    #[test]
+    fn limit_to_1_caps_ratios()
+    {
+        let exact = coins(100);
+        let noisy = PrecisionLimit { noise: 30, ..Default::default() };
+        assert!(noisy.ratios(&exact).iter().any(|r| *r > 1.0));
+        let limited = PrecisionLimit { limit_to_1: true, ..noisy };
+        assert!(limited.ratios(&exact).iter().all(|r| *r <= 1.0));
+    }
+
+    #[test]
+    fn error_skips_bins_that_are_not_finite()
+    {
+        let exact = [-1.0, f64::NEG_INFINITY, -2.0];
+        let estimate = [-1.5, -3.0, f64::NAN];
+        assert_eq!(log10_error(&estimate, &exact), Some(0.5));
+        assert_eq!(log10_error(&[f64::NAN], &[0.0]), None);
+    }
+
+    #[test]
+    fn one_over_t_reaches_saturation()
+    {
+        let threshold = 1e-6;
+        let steps = saturation_steps(200, threshold);
+        assert_eq!(steps, 2e8);
+        let error = one_over_t_error(200, steps);
+        assert!((error - saturation_error(threshold)).abs() < 1e-15, "error {error}");
+        // a hundred times as many steps are a tenth of the error
+        let later = one_over_t_error(200, 100.0 * steps);
+        assert!((later - 0.1 * error).abs() < 1e-15, "error {later}");
+    }
+
+    #[test]
+    fn expected_error_is_mean_absolute_deviation()
+    {
+        // sqrt(ln f) in ln g, i.e. 0.1 for ln f = 0.01
+        let error = expected_error(0.01) * LN_10;
+        assert!((error - 0.1 * (2.0 / PI).sqrt()).abs() < 1e-15, "error {error}");
+    }
+}
@@ -0,0 +1,399 @@
+//! Scripted presentations.
+//!
+//! A script is a text file with one instruction per line. Empty lines and
+//! everything after `#` are ignored, write `\#` for a `#` in an annotation text.
+//! `scene <caption>` starts a new scene, all following lines up to the next
+//! scene belong to it:
+//!
+//! ```text
+//! scene Wang-Landau for 1500 coins
+//! model = coins        # coins, dice <faces> or walk <end|max|area>
+//! n = 1500             # at most 200 for walk area
+//! seed = 42
+//! speed = 1.2
+//! log_scale = true
+//! ratio_view = false  # neighbour ratios g(k+1)/g(k) instead of the density
+//! noise = 0
+//! beta = -0.05
+//! show = residuals rate   # residuals, rate, heatmap, simple_hist, metropolis, reweighting, best, error
+//! hide = heatmap
+//! restart
+//! pause_at = 10        # pause after 10 seconds of run time
+//! pause                # or continue
+//! text = 0.9 -250 simple sampling gives up here
+//! arrow = 0.85 -200 0.95 -300           # from x y to x y, optionally a text
+//! region = 0.8 -400 1.0 0 the tail      # two corners, optionally a text
+//! clear_annotations
+//! ```
+//!
+//! Settings carry over to the following scenes, so every scene looks the same
+//! no matter from which direction it is reached.
+//! `restart` and `pause_at` only act when their own scene is shown.
+//! Annotations are placed on the density plot in plot coordinates of the view
+//! set by the lines before, and stay until `clear_annotations`.
+//! Annotations placed by hand are kept when the scene changes.
+
+use std::fmt;
+use crate::{ModelKind, Observable, Annotation, AnnotationKind, strip_comment};
+
+/// Optional parts of the plots that can be shown or hidden by a scene
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Curve{
+    Residuals,
+    Rate,
+    Heatmap,
+    SimpleHist,
+    Metropolis,
+    Reweighting,
+    Best,
+    /// Error of Wang-Landau over the Markov steps, with the theoretical limits
+    Error
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Setting{
+    Model(ModelKind),
+    N(usize),
+    Seed(u64),
+    Speed(f32),
+    Beta(f64),
+    LogScale(bool),
+    RatioView(bool),
+    Noise(i32),
+    Show(Curve, bool),
+    Pause(bool),
+    PauseAt(f64),
+    Restart,
+    Annotate(Annotation),
+    ClearAnnotations
+}
+
+impl Setting{
+    /// Settings that only act when their own scene is shown
+    pub fn is_one_shot(&self) -> bool
+    {
+        matches!(self, Setting::Restart | Setting::PauseAt(_))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Scene{
+    pub caption: String,
+    pub settings: Vec<Setting>
+}
+
+#[derive(Debug)]
+pub struct ScriptError{
+    /// Line of the script, starting at 1. 0 if the file could not be read
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for ScriptError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ScriptError{}
+
+#[derive(Debug)]
+pub struct Script{
+    pub scenes: Vec<Scene>
+}
+
+impl Script{
+    pub fn load(path: &str) -> Result<Self, ScriptError>
+    {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ScriptError { line: 0, message: format!("{path}: {e}") })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ScriptError>
+    {
+        let mut scenes: Vec<Scene> = Vec::new();
+        // settings carry over, so these are the values at the end of each scene,
+        // together with the line that changed one of them last
+        let mut model = None;
+        let mut n = None;
+        let mut size_line = 0;
+        let check_size = |model: Option<ModelKind>, n: Option<usize>, line: usize| {
+            match (model, n) {
+                (Some(model), Some(n)) if n > model.max_n() => Err(ScriptError {
+                    line,
+                    message: format!("n = {n} is too large for this model, at most {}", model.max_n())
+                }),
+                _ => Ok(())
+            }
+        };
+        for (idx, line) in text.lines().enumerate() {
+            let error = |message: String| ScriptError { line: idx + 1, message };
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "scene" || line.starts_with("scene ") {
+                // a scene may set the model and n in any order, so it is checked as a whole
+                check_size(model, n, size_line)?;
+                scenes.push(
+                    Scene { caption: line["scene".len()..].trim().to_owned(), settings: Vec::new() }
+                );
+                continue;
+            }
+            let scene = scenes.last_mut()
+                .ok_or_else(|| error("expected `scene` before the first setting".to_owned()))?;
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (line, "")
+            };
+            let settings = parse_setting(key, value).map_err(error)?;
+            for setting in settings.iter() {
+                match setting {
+                    Setting::Model(kind) => {
+                        model = Some(*kind);
+                        size_line = idx + 1;
+                    },
+                    Setting::N(size) => {
+                        n = Some(*size);
+                        size_line = idx + 1;
+                    },
+                    _ => ()
+                }
+            }
+            scene.settings.extend(settings);
+        }
+        check_size(model, n, size_line)?;
+        if scenes.is_empty() {
+            return Err(ScriptError { line: 0, message: "the script contains no scene".to_owned() });
+        }
+        Ok(Self { scenes })
+    }
+}
+
+fn parse_setting(key: &str, value: &str) -> Result<Vec<Setting>, String>
+{
+    let setting = match key {
+        "model" => Setting::Model(parse_model(value)?),
+        "n" => Setting::N(parse_value(key, value)?),
+        "seed" => Setting::Seed(parse_value(key, value)?),
+        "speed" => Setting::Speed(parse_value(key, value)?),
+        "beta" => Setting::Beta(parse_value(key, value)?),
+        "log_scale" => Setting::LogScale(parse_value(key, value)?),
+        "ratio_view" | "pairs" => Setting::RatioView(parse_value(key, value)?),
+        "noise" => Setting::Noise(parse_value(key, value)?),
+        "pause_at" => Setting::PauseAt(parse_value(key, value)?),
+        "pause" => Setting::Pause(true),
+        "continue" => Setting::Pause(false),
+        "restart" => Setting::Restart,
+        "clear_annotations" => Setting::ClearAnnotations,
+        "show" | "hide" => {
+            let visible = key == "show";
+            return value.split_whitespace()
+                .map(|name| parse_curve(name).map(|curve| Setting::Show(curve, visible)))
+                .collect();
+        },
+        _ => {
+            let kind = AnnotationKind::from_keyword(key)
+                .ok_or_else(|| format!("unknown setting `{key}`"))?;
+            Setting::Annotate(Annotation::parse(kind, value)?)
+        }
+    };
+    Ok(vec![setting])
+}
+
+fn parse_value<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String>
+{
+    value.parse()
+        .map_err(|_| format!("invalid value `{value}` for `{key}`"))
+}
+
+fn parse_model(value: &str) -> Result<ModelKind, String>
+{
+    let mut words = value.split_whitespace();
+    let kind = match (words.next(), words.next()) {
+        (Some("coins"), None) => ModelKind::Coins,
+        (Some("dice"), faces) => {
+            let faces = faces.unwrap_or("6");
+            match faces.parse() {
+                Ok(faces) if faces >= 2 => ModelKind::Dice { faces },
+                _ => return Err(format!("invalid number of faces `{faces}`"))
+            }
+        },
+        (Some("walk"), observable) => {
+            let observable = match observable.unwrap_or("end") {
+                "end" => Observable::End,
+                "max" => Observable::Max,
+                "area" => Observable::Area,
+                other => return Err(format!("unknown observable `{other}`"))
+            };
+            ModelKind::Walk { observable }
+        },
+        _ => return Err(format!("unknown model `{value}`"))
+    };
+    if words.next().is_some() {
+        return Err(format!("unknown model `{value}`"));
+    }
+    Ok(kind)
+}
+
+fn parse_curve(name: &str) -> Result<Curve, String>
+{
+    let curve = match name {
+        "residuals" => Curve::Residuals,
+        "rate" => Curve::Rate,
+        "heatmap" => Curve::Heatmap,
+        "simple_hist" => Curve::SimpleHist,
+        "metropolis" => Curve::Metropolis,
+        "reweighting" => Curve::Reweighting,
+        "best" => Curve::Best,
+        "error" => Curve::Error,
+        _ => return Err(format!("unknown curve `{name}`"))
+    };
+    Ok(curve)
+}
+
+/// A loaded script and the scene that is currently shown
+pub struct Presentation{
+    script: Script,
+    current: usize
+}
+
+impl Presentation{
+    pub fn new(script: Script) -> Self
+    {
+        Self { script, current: 0 }
+    }
+
+    pub fn index(&self) -> usize
+    {
+        self.current
+    }
+
+    pub fn scene_count(&self) -> usize
+    {
+        self.script.scenes.len()
+    }
+
+    pub fn scene(&self) -> &Scene
+    {
+        &self.script.scenes[self.current]
+    }
+
+    /// All settings that lead to the current scene, in the order in which they have to be applied
+    pub fn settings(&self) -> impl Iterator<Item = Setting> + '_
+    {
+        let before = self.script.scenes[..self.current]
+            .iter()
+            .flat_map(|scene| scene.settings.iter().filter(|s| !s.is_one_shot()));
+        before.chain(self.scene().settings.iter())
+            .cloned()
+    }
+
+    /// Returns false if this already is the last scene
+    pub fn next_scene(&mut self) -> bool
+    {
+        if self.current + 1 < self.scene_count() {
+            self.current += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns false if this already is the first scene
+    pub fn previous_scene(&mut self) -> bool
+    {
+        if self.current > 0 {
+            self.current -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parse_scenes()
+    {
+        let script = "# intro\nscene First one\nmodel = dice 4  # comment\nshow = residuals best\nrestart\n\
+            scene\nn = 20\ntext = 0.5 1 bin \\#2\npause_at = 3.5\n";
+        let script = Script::parse(script).unwrap();
+        assert_eq!(script.scenes.len(), 2);
+        assert_eq!(script.scenes[0].caption, "First one");
+        assert_eq!(
+            script.scenes[0].settings,
+            vec![
+                Setting::Model(ModelKind::Dice { faces: 4 }),
+                Setting::Show(Curve::Residuals, true),
+                Setting::Show(Curve::Best, true),
+                Setting::Restart
+            ]
+        );
+        assert_eq!(script.scenes[1].caption, "");
+        let Setting::Annotate(annotation) = &script.scenes[1].settings[1] else {
+            panic!("expected an annotation");
+        };
+        assert_eq!(annotation.text, "bin #2");
+
+        // one-shot settings of earlier scenes are not repeated
+        let mut presentation = Presentation::new(script);
+        assert!(presentation.next_scene());
+        assert!(!presentation.next_scene());
+        let settings: Vec<_> = presentation.settings().collect();
+        assert_eq!(settings.len(), 6);
+        assert!(!settings.contains(&Setting::Restart));
+        assert_eq!(settings.last(), Some(&Setting::PauseAt(3.5)));
+    }
+
+    #[test]
+    fn parse_errors()
+    {
+        let error = |text: &str| {
+            let error = Script::parse(text).unwrap_err();
+            (error.line, error.message)
+        };
+        assert_eq!(error("").0, 0);
+        assert_eq!(error("# only a comment").0, 0);
+        assert_eq!(error("n = 3"), (1, "expected `scene` before the first setting".to_owned()));
+        assert_eq!(error("scene\nn = many"), (2, "invalid value `many` for `n`".to_owned()));
+        assert_eq!(error("scene\nzoom = 2"), (2, "unknown setting `zoom`".to_owned()));
+        assert_eq!(error("scene\nshow = rate legend"), (2, "unknown curve `legend`".to_owned()));
+        assert_eq!(error("scene\nmodel = dice 1"), (2, "invalid number of faces `1`".to_owned()));
+        assert_eq!(error("scene\nmodel = walk area max"), (2, "unknown model `walk area max`".to_owned()));
+        assert_eq!(error("scene\nmodel = walk far"), (2, "unknown observable `far`".to_owned()));
+        assert_eq!(error("scene\n\narrow = 0 1"), (3, "expected 4 coordinates in `0 1`".to_owned()));
+        assert_eq!(Script::load("/this/file/does/not/exist").unwrap_err().line, 0);
+    }
+
+    #[test]
+    fn walk_area_is_limited()
+    {
+        let script = "scene a\nmodel = walk area\nn = 200\nscene b\nn = 201\n";
+        let error = Script::parse(script).unwrap_err();
+        assert_eq!(error.line, 5);
+
+        // the limit also applies if the model changes after n
+        let script = "scene a\nn = 1500\nscene b\nmodel = walk area\n";
+        assert_eq!(Script::parse(script).unwrap_err().line, 4);
+
+        let script = "scene a\nmodel = walk area\nn = 200\nscene b\nmodel = coins\nn = 1500\n";
+        assert!(Script::parse(script).is_ok());
+
+        // within a scene the order of model and n does not matter
+        let script = "scene a\nn = 1500\nscene b\nmodel = walk area\nn = 100\n";
+        assert!(Script::parse(script).is_ok());
+        let script = "scene a\nmodel = walk area\nn = 100\nscene b\nn = 1500\nmodel = coins\n";
+        assert!(Script::parse(script).is_ok());
+        let script = "scene a\nn = 1500\nmodel = walk area\nshow = best\nscene b\n";
+        assert_eq!(Script::parse(script).unwrap_err().line, 3);
+    }
+}
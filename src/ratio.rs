@@ -0,0 +1,83 @@
+/// log10 of the neighbour ratios g(k+1)/g(k) of a log10 density.
+/// These ratios are what the acceptance probabilities of Wang-Landau depend on.
+/// The ratio is NaN, i.e., not drawn, if one of the bins is empty or NaN
+pub fn log10_ratios(log10_density: &[f64]) -> Vec<f64>
+{
+    log10_density.windows(2)
+        .map(
+            |pair|
+            {
+                if pair[0].is_finite() && pair[1].is_finite() {
+                    pair[1] - pair[0]
+                } else {
+                    f64::NAN
+                }
+            }
+        ).collect()
+}
+
+/// Relative errors of the neighbour ratios of `log10_estimate` compared to those of `log10_exact`
+pub fn ratio_errors(log10_estimate: &[f64], log10_exact: &[f64]) -> Vec<f64>
+{
+    log10_ratios(log10_estimate)
+        .into_iter()
+        .zip(log10_ratios(log10_exact))
+        .map(|(estimate, exact)| 10_f64.powf(estimate - exact) - 1.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{ln_binomial, normalized_log10, ModelKind};
+
+    #[test]
+    fn coin_ratios_are_binomial()
+    {
+        for n in [1, 10, 101] {
+            let ratios = log10_ratios(&normalized_log10(&ln_binomial(n)));
+            assert_eq!(ratios.len(), n);
+            for (k, ratio) in ratios.iter().enumerate() {
+                let expected = (n - k) as f64 / (k + 1) as f64;
+                assert!((ratio - expected.log10()).abs() < 1e-9, "n {n} k {k}");
+                assert_eq!(ModelKind::Coins.neighbour_ratio(n, k), Some(expected));
+            }
+            assert_eq!(ModelKind::Coins.neighbour_ratio(n, n), None);
+        }
+        assert!(log10_ratios(&[]).is_empty());
+        assert!(log10_ratios(&[0.5]).is_empty());
+    }
+
+    #[test]
+    fn empty_bins_have_no_ratio()
+    {
+        let density = [0.0, f64::NEG_INFINITY, 1.0, f64::NAN, 2.0, 2.5];
+        let ratios = log10_ratios(&density);
+        assert!(ratios[..4].iter().all(|ratio| ratio.is_nan()));
+        assert_eq!(ratios[4], 0.5);
+    }
+
+    #[test]
+    fn relative_errors()
+    {
+        let exact = normalized_log10(&ln_binomial(4));
+        assert!(ratio_errors(&exact, &exact).iter().all(|error| error.abs() < 1e-12));
+
+        // twice as many states in bin 2 doubles g(2)/g(1) and halves g(3)/g(2)
+        let mut estimate = exact.clone();
+        estimate[2] += 2_f64.log10();
+        let errors = ratio_errors(&estimate, &exact);
+        let expected = [0.0, 1.0, -0.5, 0.0];
+        for (error, expected) in errors.iter().zip(expected) {
+            assert!((error - expected).abs() < 1e-12, "{errors:?}");
+        }
+
+        // an empty bin of the estimate gives no error instead of an infinite one
+        estimate[2] = f64::NAN;
+        let errors = ratio_errors(&estimate, &exact);
+        assert!(errors[1].is_nan() && errors[2].is_nan());
+        assert!(errors[0].abs() < 1e-12);
+        // missing exact results give no errors at all
+        assert!(ratio_errors(&estimate, &[]).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+use sampling::*;
+use crate::{CoinSeq, Canonical};
+
+/// Everything that is displayed in the plots at one point in time
+#[derive(Clone)]
+pub struct Snapshot{
+    /// Run time in seconds
+    pub time: f64,
+    /// normalized log10 of the Wang-Landau estimate
+    pub wl_density: Vec<f64>,
+    /// normalized log10 of the entropic estimate, empty if entropic sampling did not start yet
+    pub e_density: Vec<f64>,
+    /// probabilities measured by simple sampling
+    pub s_prob: Vec<f64>,
+    /// probabilities measured by the Metropolis chain, empty if it does not run
+    pub m_prob: Vec<f64>,
+    pub wl_hist: Vec<usize>,
+    pub e_hist: Vec<usize>,
+    pub s_hist: Vec<usize>,
+    pub m_hist: Vec<usize>
+}
+
+impl Snapshot{
+    pub fn from_sim(c: &CoinSeq, time: f64) -> Self
+    {
+        let (mut wl_density, wl_hist) = {
+            let wl = c.wl.read().unwrap();
+            (wl.log_density_base10(), wl.hist().hist().clone())
+        };
+        norm_log10_sum_to_1(&mut wl_density);
+
+        let (e_density, e_hist) = match &c.entr {
+            Some(entr) => {
+                let mut e_density: Vec<_> = entr.log_density_estimate()
+                    .iter().map(|val|  *val * std::f64::consts::LOG10_E)
+                    .collect();
+                norm_log10_sum_to_1(&mut e_density);
+                (e_density, entr.hist().hist().clone())
+            },
+            None => (Vec::new(), Vec::new())
+        };
+
+        let (s_prob, s_hist) = {
+            let simple = c.simple.lock();
+            (simple.get_prob(), simple.hist.hist().clone())
+        };
+
+        let (m_prob, m_hist) = match &c.metropolis {
+            Some(metropolis) => {
+                let metropolis = metropolis.lock();
+                let m_hist = metropolis.hist().hist().clone();
+                let m_prob = Canonical::from_hist(&m_hist)
+                    .map(|canonical| canonical.prob)
+                    .unwrap_or_default();
+                (m_prob, m_hist)
+            },
+            None => (Vec::new(), Vec::new())
+        };
+
+        Self{
+            time,
+            wl_density,
+            e_density,
+            s_prob,
+            m_prob,
+            wl_hist,
+            e_hist,
+            s_hist,
+            m_hist
+        }
+    }
+
+    /// Number of stored densities and hits
+    fn values(&self) -> usize
+    {
+        self.wl_density.len() + self.e_density.len()
+            + self.s_prob.len() + self.m_prob.len()
+            + self.wl_hist.len() + self.e_hist.len()
+            + self.s_hist.len() + self.m_hist.len()
+    }
+
+    /// log10 of the simple sampling probabilities, NaN for bins without hits
+    pub fn s_prob_log10(&self) -> Vec<f64>
+    {
+        log10_or_nan(&self.s_prob)
+    }
+
+    /// log10 of the Metropolis probabilities, NaN for bins without hits
+    pub fn m_prob_log10(&self) -> Vec<f64>
+    {
+        log10_or_nan(&self.m_prob)
+    }
+}
+
+fn log10_or_nan(prob: &[f64]) -> Vec<f64>
+{
+    prob.iter()
+        .map(
+            |val|
+            {
+                if *val > 0.0 {
+                    val.log10()
+                } else {
+                    f64::NAN
+                }
+            }
+        ).collect()
+}
+
+/// Upper limit for the number of values in all snapshots together, i.e., about 160 MB
+const MAX_VALUES: usize = 20_000_000;
+/// Upper limit for the number of snapshots, even if the system is tiny
+const MAX_SNAPSHOTS: usize = 1000;
+
+/// Periodic snapshots of a run
+pub struct Recording{
+    pub enabled: bool,
+    /// Minimal run time between two snapshots in seconds
+    pub interval: f64,
+    /// How often every second snapshot was dropped. Each time doubles the interval
+    thinned: i32,
+    snapshots: Vec<Snapshot>
+}
+
+impl Default for Recording{
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 0.5,
+            thinned: 0,
+            snapshots: Vec::new()
+        }
+    }
+}
+
+impl Recording{
+    pub fn clear(&mut self)
+    {
+        self.snapshots.clear();
+        self.thinned = 0;
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.snapshots.is_empty()
+    }
+
+    /// Run time of the last snapshot
+    pub fn duration(&self) -> f64
+    {
+        self.snapshots
+            .last()
+            .map_or(0.0, |snap| snap.time)
+    }
+
+    /// Stores a snapshot, if enough time passed since the last one
+    pub fn record(&mut self, c: &CoinSeq, time: f64)
+    {
+        if self.enabled && self.is_due(time) {
+            self.push(Snapshot::from_sim(c, time));
+        }
+    }
+
+    fn is_due(&self, time: f64) -> bool
+    {
+        let interval = self.interval * 2_f64.powi(self.thinned);
+        self.snapshots
+            .last()
+            .is_none_or(|snap| time - snap.time >= interval)
+    }
+
+    fn push(&mut self, snapshot: Snapshot)
+    {
+        let max = (MAX_VALUES / snapshot.values().max(1)).clamp(2, MAX_SNAPSHOTS);
+        if self.snapshots.len() >= max {
+            // keep every second snapshot, so the whole run can still be replayed
+            self.snapshots = std::mem::take(&mut self.snapshots)
+                .into_iter()
+                .step_by(2)
+                .collect();
+            self.thinned += 1;
+        }
+        self.snapshots.push(snapshot);
+    }
+
+    /// Last snapshot that was taken at or before `time`
+    pub fn at(&self, time: f64) -> Option<&Snapshot>
+    {
+        let idx = self.snapshots.partition_point(|snap| snap.time <= time);
+        self.snapshots
+            .get(idx.saturating_sub(1))
+    }
+}
+
+/// State of the timeline while replaying a recording
+pub struct Replay{
+    /// Current position on the timeline in seconds
+    pub time: f64,
+    /// Replay speed, negative values replay backwards
+    pub speed: f64,
+    pub playing: bool
+}
+
+impl Default for Replay{
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            speed: 1.0,
+            playing: false
+        }
+    }
+}
+
+impl Replay{
+    /// Moves along the timeline, stops at both ends
+    pub fn advance(&mut self, dt: f64, duration: f64)
+    {
+        if !self.playing {
+            return;
+        }
+        self.time += dt * self.speed;
+        if self.time <= 0.0 || self.time >= duration {
+            self.time = self.time.clamp(0.0, duration);
+            self.playing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn snapshot(time: f64, bins: usize) -> Snapshot
+    {
+        Snapshot{
+            time,
+            wl_density: vec![0.0; bins],
+            e_density: Vec::new(),
+            s_prob: vec![0.0; bins],
+            m_prob: Vec::new(),
+            wl_hist: vec![0; bins],
+            e_hist: Vec::new(),
+            s_hist: vec![0; bins],
+            m_hist: Vec::new()
+        }
+    }
+
+    /// Records like `Recording::record`, but without a simulation
+    fn record(recording: &mut Recording, time: f64)
+    {
+        if recording.is_due(time) {
+            recording.push(snapshot(time, 10));
+        }
+    }
+
+    #[test]
+    fn respects_interval()
+    {
+        let mut recording = Recording { interval: 1.0, ..Default::default() };
+        assert!(recording.is_empty());
+        assert_eq!(recording.duration(), 0.0);
+        for time in [0.0, 0.5, 1.0, 1.9, 2.0, 3.5] {
+            record(&mut recording, time);
+        }
+        let times: Vec<f64> = recording.snapshots.iter().map(|snap| snap.time).collect();
+        assert_eq!(times, vec![0.0, 1.0, 2.0, 3.5]);
+        assert_eq!(recording.duration(), 3.5);
+    }
+
+    #[test]
+    fn lookup_by_time()
+    {
+        let mut recording = Recording { interval: 1.0, ..Default::default() };
+        assert!(recording.at(1.0).is_none());
+        for time in [1.0, 2.0, 3.0] {
+            record(&mut recording, time);
+        }
+        assert_eq!(recording.at(0.5).unwrap().time, 1.0);
+        assert_eq!(recording.at(2.0).unwrap().time, 2.0);
+        assert_eq!(recording.at(2.9).unwrap().time, 2.0);
+        assert_eq!(recording.at(10.0).unwrap().time, 3.0);
+    }
+
+    #[test]
+    fn thinning_keeps_whole_run()
+    {
+        let mut recording = Recording { interval: 1.0, ..Default::default() };
+        for i in 0..=MAX_SNAPSHOTS {
+            record(&mut recording, i as f64);
+        }
+        assert_eq!(recording.snapshots.len(), MAX_SNAPSHOTS / 2 + 1);
+        assert_eq!(recording.snapshots[0].time, 0.0);
+        assert_eq!(recording.snapshots[1].time, 2.0);
+        assert_eq!(recording.duration(), MAX_SNAPSHOTS as f64);
+
+        // the interval doubled, the user setting did not change
+        let last = recording.duration();
+        assert!(!recording.is_due(last + 1.5));
+        assert!(recording.is_due(last + 2.0));
+        assert_eq!(recording.interval, 1.0);
+
+        recording.clear();
+        assert!(recording.is_empty());
+        record(&mut recording, 0.0);
+        assert!(recording.is_due(1.0));
+    }
+
+    #[test]
+    fn large_snapshots_are_thinned_earlier()
+    {
+        let bins = MAX_VALUES / 4 / 10;
+        let max = MAX_VALUES / snapshot(0.0, bins).values();
+        assert_eq!(max, 10);
+        let mut recording = Recording::default();
+        for i in 0..=max {
+            recording.push(snapshot(i as f64, bins));
+        }
+        assert_eq!(recording.snapshots.len(), max / 2 + 1);
+    }
+}
@@ -0,0 +1,248 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+use crate::Texts;
+
+/// Everything that can be triggered by the keyboard
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action{
+    Start,
+    Pause,
+    LogScale,
+    RatioView,
+    Noise,
+    HistScale,
+    ZoomIn,
+    ZoomOut,
+    NextScene,
+    PreviousScene,
+    SidePanel,
+    Fullscreen,
+    Help
+}
+
+impl Action{
+    pub const ALL: [Action; 13] = [
+        Action::Start,
+        Action::Pause,
+        Action::LogScale,
+        Action::RatioView,
+        Action::Noise,
+        Action::HistScale,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::NextScene,
+        Action::PreviousScene,
+        Action::SidePanel,
+        Action::Fullscreen,
+        Action::Help
+    ];
+
+    /// Name in the configuration file
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            Action::Start => "start",
+            Action::Pause => "pause",
+            Action::LogScale => "log_scale",
+            Action::RatioView => "ratio_view",
+            Action::Noise => "noise",
+            Action::HistScale => "hist_scale",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::NextScene => "next_scene",
+            Action::PreviousScene => "previous_scene",
+            Action::SidePanel => "side_panel",
+            Action::Fullscreen => "fullscreen",
+            Action::Help => "help"
+        }
+    }
+
+    pub fn description(&self, t: &'static Texts) -> &'static str
+    {
+        match self {
+            Action::Start => t.action_start,
+            Action::Pause => t.action_pause,
+            Action::LogScale => t.action_log_scale,
+            Action::RatioView => t.action_ratio_view,
+            Action::Noise => t.action_noise,
+            Action::HistScale => t.action_hist_scale,
+            Action::ZoomIn => t.action_zoom_in,
+            Action::ZoomOut => t.action_zoom_out,
+            Action::NextScene => t.action_next_scene,
+            Action::PreviousScene => t.action_previous_scene,
+            Action::SidePanel => t.action_side_panel,
+            Action::Fullscreen => t.action_fullscreen,
+            Action::Help => t.action_help
+        }
+    }
+
+    fn default_keys(&self) -> &'static [Key]
+    {
+        match self {
+            Action::Start => &[Key::R],
+            Action::Pause => &[Key::Space],
+            Action::LogScale => &[Key::L],
+            Action::RatioView => &[Key::P],
+            Action::Noise => &[Key::N],
+            Action::HistScale => &[Key::H],
+            Action::ZoomIn => &[Key::Plus],
+            Action::ZoomOut => &[Key::Minus],
+            Action::NextScene => &[Key::ArrowRight, Key::PageDown],
+            Action::PreviousScene => &[Key::ArrowLeft, Key::PageUp],
+            Action::SidePanel => &[Key::F2],
+            Action::Fullscreen => &[Key::F11],
+            Action::Help => &[Key::F1]
+        }
+    }
+}
+
+/// Keyboard shortcuts of all actions. An action can have several shortcuts,
+/// e.g., for the keyboard and for a presenter
+pub struct Shortcuts{
+    bindings: Vec<(Action, KeyboardShortcut)>
+}
+
+impl Default for Shortcuts{
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .flat_map(
+                |action|
+                action.default_keys()
+                    .iter()
+                    .map(|key| (*action, KeyboardShortcut::new(Modifiers::NONE, *key)))
+            ).collect();
+        Self { bindings }
+    }
+}
+
+impl Shortcuts{
+    /// Reads a file with lines like `pause = Space, Ctrl+P`.
+    /// Actions that are not mentioned keep their default shortcuts
+    pub fn load(path: &str) -> Result<Self, String>
+    {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("{path}: {e}"))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        let mut shortcuts = Self::default();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.split('#')
+                .next()
+                .unwrap()
+                .trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {message}", idx + 1);
+            let (name, keys) = line.split_once('=')
+                .ok_or_else(|| error("expected `action = keys`".to_owned()))?;
+            let name = name.trim();
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| error(format!("unknown action `{name}`")))?;
+            let keys = keys.split(',')
+                .map(str::trim)
+                .filter(|keys| !keys.is_empty())
+                .map(parse_shortcut)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(error)?;
+            shortcuts.bindings.retain(|(a, _)| *a != action);
+            shortcuts.bindings.extend(keys.into_iter().map(|shortcut| (action, shortcut)));
+        }
+        Ok(shortcuts)
+    }
+
+    /// All shortcuts of `action`
+    pub fn of(&self, action: Action) -> impl Iterator<Item = &KeyboardShortcut>
+    {
+        self.bindings
+            .iter()
+            .filter(move |(a, _)| *a == action)
+            .map(|(_, shortcut)| shortcut)
+    }
+
+    /// Actions whose shortcut was pressed in this frame. The key presses are consumed.
+    /// Nothing is triggered while a text field has the focus
+    pub fn pressed(&self, ctx: &egui::Context) -> Vec<Action>
+    {
+        if ctx.wants_keyboard_input() {
+            return Vec::new();
+        }
+        ctx.input_mut(
+            |i|
+            {
+                self.bindings
+                    .iter()
+                    .filter(|(_, shortcut)| i.consume_shortcut(shortcut))
+                    .map(|(action, _)| *action)
+                    .collect()
+            }
+        )
+    }
+}
+
+/// Parses shortcuts like `Ctrl+Shift+P`. The key itself is named like in egui, e.g., `Plus` or `F1`
+fn parse_shortcut(text: &str) -> Result<KeyboardShortcut, String>
+{
+    let mut parts: Vec<&str> = text.split('+')
+        .map(str::trim)
+        .collect();
+    let key_name = parts.pop().unwrap();
+    let key = Key::from_name(key_name)
+        .ok_or_else(|| format!("unknown key `{key_name}`"))?;
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        modifiers = modifiers | match part {
+            "Ctrl" => Modifiers::CTRL,
+            "Shift" => Modifiers::SHIFT,
+            "Alt" => Modifiers::ALT,
+            "Cmd" => Modifiers::COMMAND,
+            _ => return Err(format!("unknown modifier `{part}`"))
+        };
+    }
+    Ok(KeyboardShortcut::new(modifiers, key))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parse_replaces_only_mentioned_actions()
+    {
+        let shortcuts = Shortcuts::parse("# comment\npause = Space, Ctrl+Shift+P\n\nhelp =  \n").unwrap();
+        let pause: Vec<_> = shortcuts.of(Action::Pause).copied().collect();
+        assert_eq!(
+            pause,
+            vec![
+                KeyboardShortcut::new(Modifiers::NONE, Key::Space),
+                KeyboardShortcut::new(Modifiers::CTRL | Modifiers::SHIFT, Key::P)
+            ]
+        );
+        assert_eq!(shortcuts.of(Action::Help).count(), 0);
+        let defaults = Shortcuts::default();
+        assert!(shortcuts.of(Action::Fullscreen).eq(defaults.of(Action::Fullscreen)));
+    }
+
+    #[test]
+    fn parse_errors()
+    {
+        let error = |text| Shortcuts::parse(text).err().unwrap();
+        assert_eq!(error("pause"), "line 1: expected `action = keys`");
+        assert_eq!(error("\njump = J"), "line 2: unknown action `jump`");
+        assert_eq!(error("pause = Spacebar"), "line 1: unknown key `Spacebar`");
+        assert_eq!(error("pause = Super+P"), "line 1: unknown modifier `Super`");
+    }
+
+    #[test]
+    fn names_are_unique()
+    {
+        for (idx, action) in Action::ALL.iter().enumerate() {
+            assert!(Action::ALL[idx + 1..].iter().all(|other| other.name() != action.name()));
+        }
+    }
+}
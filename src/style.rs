@@ -0,0 +1,177 @@
+use std::ops::{Index, IndexMut};
+use egui::Color32;
+use egui_plot::{Line, MarkerShape, PlotUi, Points};
+use crate::{Palette, SvgKind, SvgSeries, Texts};
+
+/// Curves of the density plot that can be styled individually
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Series{
+    Analytic,
+    Wl,
+    Entropic,
+    Simple,
+    Metropolis,
+    /// Best estimate possible with the chosen precision of log f.
+    /// Its color is also used for other reference curves
+    Best
+}
+
+impl Series{
+    pub const ALL: [Series; 6] = [
+        Series::Analytic,
+        Series::Wl,
+        Series::Entropic,
+        Series::Simple,
+        Series::Metropolis,
+        Series::Best
+    ];
+
+    pub fn name(&self, t: &'static Texts) -> &'static str
+    {
+        match self {
+            Series::Analytic => t.analytic_results,
+            Series::Wl => t.wl_results,
+            Series::Entropic => t.entropic_results,
+            Series::Simple => t.simple_results,
+            Series::Metropolis => t.metropolis_results,
+            Series::Best => t.best
+        }
+    }
+
+    /// Default line width or marker radius relative to the width of a normal line
+    fn relative_width(&self) -> f32
+    {
+        match self {
+            Series::Analytic => 2.0,
+            Series::Wl | Series::Entropic => 1.0,
+            Series::Simple | Series::Metropolis => 0.9,
+            Series::Best => 0.7
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SeriesStyle{
+    pub color: Color32,
+    /// Line width or marker radius
+    pub width: f32,
+    /// Draw markers instead of a line
+    pub points: bool,
+    pub marker: MarkerShape,
+    pub visible: bool
+}
+
+impl SeriesStyle{
+    fn line(color: Color32, width: f32) -> Self
+    {
+        Self { color, width, points: false, marker: MarkerShape::Circle, visible: true }
+    }
+
+    fn markers(color: Color32, width: f32, marker: MarkerShape) -> Self
+    {
+        Self { color, width, points: true, marker, visible: true }
+    }
+
+    /// Adds the series to the plot, unless it is hidden
+    pub fn show(&self, plot_ui: &mut PlotUi, name: &str, data: &[[f64;2]])
+    {
+        if !self.visible {
+            return;
+        }
+        if self.points {
+            let points = Points::new(data.to_vec())
+                .name(name)
+                .radius(self.width)
+                .shape(self.marker)
+                .color(self.color);
+            plot_ui.points(points);
+        } else {
+            let line = Line::new(data.to_vec())
+                .name(name)
+                .width(self.width)
+                .color(self.color);
+            plot_ui.line(line);
+        }
+    }
+
+    /// The svg export knows only crosses and circles as markers
+    pub fn svg<'a>(&self, name: &'a str, points: &'a [[f64;2]]) -> SvgSeries<'a>
+    {
+        let kind = match (self.points, self.marker) {
+            (false, _) => SvgKind::Line,
+            (true, MarkerShape::Cross) => SvgKind::Crosses,
+            (true, _) => SvgKind::Points
+        };
+        SvgSeries { name, points, color: self.color, width: self.width, kind }
+    }
+}
+
+/// Style of every [`Series`], index it with the series
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Styles{
+    styles: [SeriesStyle; 6]
+}
+
+impl Styles{
+    /// Default styles for a palette, `linewidth` is the width of a normal line
+    pub fn new(palette: &Palette, linewidth: f32) -> Self
+    {
+        let styles = Series::ALL.map(
+            |series|
+            {
+                let width = linewidth * series.relative_width();
+                match series {
+                    Series::Analytic => SeriesStyle::line(palette.analytic, width),
+                    Series::Wl => SeriesStyle::line(palette.wl, width),
+                    Series::Entropic => SeriesStyle::line(palette.entropic, width),
+                    Series::Simple => SeriesStyle::markers(palette.simple, width, MarkerShape::Cross),
+                    Series::Metropolis => SeriesStyle::markers(palette.metropolis, width, MarkerShape::Circle),
+                    Series::Best => SeriesStyle::markers(palette.reference, width, MarkerShape::Circle)
+                }
+            }
+        );
+        Self { styles }
+    }
+
+    /// Sets the widths of all series for a new width of a normal line.
+    /// Colors, markers and visibility stay as they are
+    pub fn set_linewidth(&mut self, linewidth: f32)
+    {
+        for series in Series::ALL {
+            self[series].width = linewidth * series.relative_width();
+        }
+    }
+}
+
+impl Index<Series> for Styles{
+    type Output = SeriesStyle;
+
+    fn index(&self, series: Series) -> &SeriesStyle
+    {
+        &self.styles[series as usize]
+    }
+}
+
+impl IndexMut<Series> for Styles{
+    fn index_mut(&mut self, series: Series) -> &mut SeriesStyle
+    {
+        &mut self.styles[series as usize]
+    }
+}
+
+/// Symbol of the marker, for the style editor
+pub fn marker_symbol(marker: MarkerShape) -> &'static str
+{
+    match marker {
+        MarkerShape::Circle => "●",
+        MarkerShape::Diamond => "◆",
+        MarkerShape::Square => "■",
+        MarkerShape::Cross => "×",
+        MarkerShape::Plus => "+",
+        MarkerShape::Up => "▲",
+        MarkerShape::Down => "▼",
+        MarkerShape::Left => "◀",
+        MarkerShape::Right => "▶",
+        MarkerShape::Asterisk => "*"
+    }
+}
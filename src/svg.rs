@@ -0,0 +1,354 @@
+use std::fmt::Write;
+use egui::Color32;
+use crate::exchange;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 420.0;
+const LEFT: f64 = 80.0;
+const RIGHT: f64 = 20.0;
+const TOP: f64 = 20.0;
+const BOTTOM: f64 = 60.0;
+const FONT: &str = "DejaVu Sans, Helvetica, Arial, sans-serif";
+
+pub enum SvgKind{
+    Line,
+    Points,
+    Crosses
+}
+
+pub struct SvgSeries<'a>{
+    pub name: &'a str,
+    pub points: &'a [[f64;2]],
+    pub color: Color32,
+    /// line width or marker radius
+    pub width: f32,
+    pub kind: SvgKind
+}
+
+/// A static version of a plot of the app
+pub struct SvgPlot<'a>{
+    pub x_label: &'a str,
+    pub y_label: &'a str,
+    /// y values are exponents, the tick labels are drawn as 10ˣ
+    pub log_y: bool,
+    pub series: Vec<SvgSeries<'a>>
+}
+
+impl SvgPlot<'_>{
+    pub fn save(&self, path: &str) -> std::io::Result<()>
+    {
+        std::fs::write(path, self.render())
+    }
+
+    pub fn render(&self) -> String
+    {
+        let (x_min, x_max) = bounds(self.series.iter().flat_map(|s| s.points.iter().map(|p| p[0])));
+        let (y_min, y_max) = bounds(self.series.iter().flat_map(|s| s.points.iter().map(|p| p[1])));
+        let x_ticks = ticks(x_min, x_max);
+        let y_ticks = ticks(y_min, y_max);
+        // the axes cover the ticks, so the ticks are at the border
+        let x_min = x_min.min(x_ticks[0]);
+        let x_max = x_max.max(*x_ticks.last().unwrap());
+        let y_min = y_min.min(y_ticks[0]);
+        let y_max = y_max.max(*y_ticks.last().unwrap());
+
+        let plot_w = WIDTH - LEFT - RIGHT;
+        let plot_h = HEIGHT - TOP - BOTTOM;
+        let to_x = |x: f64| LEFT + (x - x_min) / (x_max - x_min) * plot_w;
+        let to_y = |y: f64| TOP + (y_max - y) / (y_max - y_min) * plot_h;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="{FONT}" font-size="13">"#
+        ).unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+        writeln!(
+            svg,
+            r#"<clipPath id="plot_area"><rect x="{LEFT}" y="{TOP}" width="{plot_w}" height="{plot_h}"/></clipPath>"#
+        ).unwrap();
+
+        // grid and tick labels
+        for x in x_ticks.iter() {
+            let px = to_x(*x);
+            writeln!(
+                svg,
+                r##"<line x1="{px:.2}" y1="{TOP}" x2="{px:.2}" y2="{:.2}" stroke="#dddddd" stroke-width="0.8"/>"##,
+                TOP + plot_h
+            ).unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{px:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+                TOP + plot_h + 18.0,
+                format_tick(*x)
+            ).unwrap();
+        }
+        for y in y_ticks.iter() {
+            let py = to_y(*y);
+            writeln!(
+                svg,
+                r##"<line x1="{LEFT}" y1="{py:.2}" x2="{:.2}" y2="{py:.2}" stroke="#dddddd" stroke-width="0.8"/>"##,
+                LEFT + plot_w
+            ).unwrap();
+            let label = if self.log_y {
+                let ex: String = format_tick(*y).chars().map(exchange).collect();
+                format!("10{ex}")
+            } else {
+                format_tick(*y)
+            };
+            writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" text-anchor="end">{label}</text>"#,
+                LEFT - 6.0,
+                py + 4.5
+            ).unwrap();
+        }
+        writeln!(
+            svg,
+            r#"<rect x="{LEFT}" y="{TOP}" width="{plot_w}" height="{plot_h}" fill="none" stroke="black" stroke-width="1"/>"#
+        ).unwrap();
+
+        // axis labels
+        writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="middle" font-size="15">{}</text>"#,
+            LEFT + plot_w * 0.5,
+            HEIGHT - 12.0,
+            escape(self.x_label)
+        ).unwrap();
+        writeln!(
+            svg,
+            r#"<text transform="translate(18 {:.2}) rotate(-90)" text-anchor="middle" font-size="15">{}</text>"#,
+            TOP + plot_h * 0.5,
+            escape(self.y_label)
+        ).unwrap();
+
+        // data
+        writeln!(svg, r#"<g clip-path="url(#plot_area)">"#).unwrap();
+        for series in self.series.iter() {
+            let color = hex(series.color);
+            let w = series.width;
+            match series.kind {
+                SvgKind::Line => {
+                    // NaN and inf interrupt the line
+                    for segment in series.points.split(|p| !p[0].is_finite() || !p[1].is_finite()) {
+                        if segment.len() < 2 {
+                            continue;
+                        }
+                        let points: Vec<_> = segment.iter()
+                            .map(|p| format!("{:.2},{:.2}", to_x(p[0]), to_y(p[1])))
+                            .collect();
+                        writeln!(
+                            svg,
+                            r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="{w}" stroke-linejoin="round"/>"#,
+                            points.join(" ")
+                        ).unwrap();
+                    }
+                },
+                SvgKind::Points => {
+                    for p in finite(series.points) {
+                        writeln!(
+                            svg,
+                            r#"<circle cx="{:.2}" cy="{:.2}" r="{w}" fill="{color}"/>"#,
+                            to_x(p[0]),
+                            to_y(p[1])
+                        ).unwrap();
+                    }
+                },
+                SvgKind::Crosses => {
+                    for p in finite(series.points) {
+                        cross(&mut svg, to_x(p[0]), to_y(p[1]), w as f64, &color);
+                    }
+                }
+            }
+        }
+        writeln!(svg, "</g>").unwrap();
+
+        // legend in the right bottom corner, like in the app
+        let entry_h = 18.0;
+        let legend_w = 170.0;
+        let legend_h = entry_h * self.series.len() as f64 + 8.0;
+        let lx = LEFT + plot_w - legend_w - 8.0;
+        let ly = TOP + plot_h - legend_h - 8.0;
+        writeln!(
+            svg,
+            r##"<rect x="{lx:.2}" y="{ly:.2}" width="{legend_w}" height="{legend_h:.2}" fill="white" fill-opacity="0.8" stroke="#999999" stroke-width="0.8"/>"##
+        ).unwrap();
+        for (i, series) in self.series.iter().enumerate() {
+            let color = hex(series.color);
+            let y = ly + 4.0 + entry_h * (i as f64 + 0.5);
+            let w = series.width;
+            match series.kind {
+                SvgKind::Line => {
+                    writeln!(
+                        svg,
+                        r#"<line x1="{:.2}" y1="{y:.2}" x2="{:.2}" y2="{y:.2}" stroke="{color}" stroke-width="{w}"/>"#,
+                        lx + 8.0,
+                        lx + 30.0
+                    ).unwrap();
+                },
+                SvgKind::Points => {
+                    writeln!(
+                        svg,
+                        r#"<circle cx="{:.2}" cy="{y:.2}" r="{}" fill="{color}"/>"#,
+                        lx + 19.0,
+                        w.max(2.0)
+                    ).unwrap();
+                },
+                SvgKind::Crosses => {
+                    cross(&mut svg, lx + 19.0, y, (w as f64).max(3.0), &color);
+                }
+            }
+            writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+                lx + 38.0,
+                y + 4.5,
+                escape(series.name)
+            ).unwrap();
+        }
+        writeln!(svg, "</svg>").unwrap();
+        svg
+    }
+}
+
+fn finite(points: &[[f64;2]]) -> impl Iterator<Item = &[f64;2]>
+{
+    points.iter()
+        .filter(|p| p[0].is_finite() && p[1].is_finite())
+}
+
+fn cross(svg: &mut String, x: f64, y: f64, r: f64, color: &str)
+{
+    writeln!(
+        svg,
+        r#"<path d="M{:.2} {:.2}L{:.2} {:.2}M{:.2} {:.2}L{:.2} {:.2}" stroke="{color}" stroke-width="1"/>"#,
+        x - r, y - r, x + r, y + r,
+        x - r, y + r, x + r, y - r
+    ).unwrap();
+}
+
+fn bounds<I>(iter: I) -> (f64, f64)
+where I: Iterator<Item = f64>
+{
+    let (min, max) = iter.filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)));
+    if !min.is_finite() {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    }
+}
+
+/// About 5 ticks at multiples of 1, 2 or 5 times a power of ten
+fn ticks(min: f64, max: f64) -> Vec<f64>
+{
+    let raw = (max - min) / 5.0;
+    let magnitude = 10_f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap();
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last)
+        .map(|i| i as f64 * step)
+        .collect()
+}
+
+fn format_tick(val: f64) -> String
+{
+    // get rid of rounding errors like 0.30000000000000004
+    let rounded = (val * 1e9).round() / 1e9;
+    if rounded == 0.0 {
+        "0".to_owned()
+    } else {
+        format!("{rounded}")
+    }
+}
+
+fn hex(color: Color32) -> String
+{
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn escape(text: &str) -> String
+{
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn linear_ticks()
+    {
+        let labels: Vec<_> = ticks(0.0, 1.0).iter().map(|tick| format_tick(*tick)).collect();
+        assert_eq!(labels, ["0", "0.2", "0.4", "0.6", "0.8", "1"]);
+        // the ticks cover the range
+        assert_eq!(ticks(3.0, 47.0), [0.0, 10.0, 20.0, 30.0, 40.0, 50.0]);
+    }
+
+    #[test]
+    fn log_ticks()
+    {
+        // for a log scale the values are exponents
+        assert_eq!(ticks(-12.3, -0.1), [-15.0, -10.0, -5.0, 0.0]);
+        let plot = SvgPlot {
+            x_label: "x",
+            y_label: "y",
+            log_y: true,
+            series: vec![
+                SvgSeries { name: "a", points: &[[0.0, -12.3], [1.0, -0.1]], color: Color32::RED, width: 1.0, kind: SvgKind::Line }
+            ]
+        };
+        let svg = plot.render();
+        assert!(svg.contains(">10⁻¹⁵</text>"));
+        assert!(svg.contains(">10⁰</text>"));
+    }
+
+    #[test]
+    fn bounds_ignore_nan_and_inf()
+    {
+        let vals = [f64::NAN, 3.0, f64::NEG_INFINITY, 1.0, f64::INFINITY];
+        assert_eq!(bounds(vals.into_iter()), (1.0, 3.0));
+        assert_eq!(bounds([f64::NAN, f64::NEG_INFINITY].into_iter()), (0.0, 1.0));
+        assert_eq!(bounds(std::iter::empty()), (0.0, 1.0));
+        assert_eq!(bounds([2.0, f64::NAN].into_iter()), (1.5, 2.5));
+    }
+
+    #[test]
+    fn render_smoke_test()
+    {
+        let line = [[0.0, 1.0], [1.0, 2.0], [2.0, f64::NAN], [3.0, 1.5], [4.0, 0.5], [5.0, f64::NEG_INFINITY]];
+        let points = [[0.0, 1.0], [1.0, f64::NAN], [2.0, 3.0]];
+        let plot = SvgPlot {
+            x_label: "energy <E>",
+            y_label: "P & Q",
+            log_y: false,
+            series: vec![
+                SvgSeries { name: "line", points: &line, color: Color32::from_rgb(1, 2, 255), width: 1.5, kind: SvgKind::Line },
+                SvgSeries { name: "points", points: &points, color: Color32::BLACK, width: 2.0, kind: SvgKind::Points },
+                SvgSeries { name: "crosses", points: &points, color: Color32::BLACK, width: 3.0, kind: SvgKind::Crosses }
+            ]
+        };
+        let svg = plot.render();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // NaN splits the line into two parts
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(r##"stroke="#0102ff""##));
+        // two data points and one legend entry
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert!(svg.contains("energy &lt;E&gt;"));
+        assert!(svg.contains("P &amp; Q"));
+        assert!(!svg.contains("NaN") && !svg.contains("inf"));
+    }
+}
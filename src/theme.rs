@@ -0,0 +1,108 @@
+use egui::{Color32, Stroke, Visuals};
+use crate::Texts;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Theme{
+    Light,
+    Dark,
+    /// White background, black text, thick and saturated lines
+    Projector,
+    /// Okabe-Ito colors, which stay distinguishable for all common kinds of color blindness
+    Colorblind
+}
+
+/// Colors of the curves
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Palette{
+    pub analytic: Color32,
+    pub wl: Color32,
+    pub entropic: Color32,
+    pub metropolis: Color32,
+    pub simple: Color32,
+    /// Best estimate and other reference curves
+    pub reference: Color32
+}
+
+impl Theme{
+    pub const ALL: [Theme; 4] = [
+        Theme::Light,
+        Theme::Dark,
+        Theme::Projector,
+        Theme::Colorblind
+    ];
+
+    pub fn name(&self, t: &'static Texts) -> &'static str
+    {
+        match self {
+            Theme::Light => t.theme_light,
+            Theme::Dark => t.theme_dark,
+            Theme::Projector => t.theme_projector,
+            Theme::Colorblind => t.theme_colorblind
+        }
+    }
+
+    pub fn visuals(&self) -> Visuals
+    {
+        match self {
+            Theme::Light | Theme::Colorblind => Visuals::light(),
+            Theme::Dark => Visuals::dark(),
+            Theme::Projector => {
+                let mut visuals = Visuals::light();
+                visuals.override_text_color = Some(Color32::BLACK);
+                visuals.panel_fill = Color32::WHITE;
+                visuals.window_fill = Color32::WHITE;
+                visuals.extreme_bg_color = Color32::WHITE;
+                visuals.widgets.noninteractive.bg_stroke = Stroke::new(1.5, Color32::BLACK);
+                visuals
+            }
+        }
+    }
+
+    pub fn palette(&self) -> Palette
+    {
+        match self {
+            Theme::Light => Palette{
+                analytic: Color32::from_rgb(0x_D8, 0x_1B, 0x_60),
+                wl: Color32::from_rgb(0x_1E, 0x_88, 0x_E5),
+                entropic: Color32::from_rgb(0x_F5, 0x_7C, 0x_00),
+                metropolis: Color32::from_rgb(0x_00, 0x_4D, 0x_40),
+                simple: Color32::BLACK,
+                reference: Color32::DARK_GRAY
+            },
+            Theme::Dark => Palette{
+                analytic: Color32::from_rgb(0x_F0, 0x_62, 0x_92),
+                wl: Color32::from_rgb(0x_64, 0x_B5, 0x_F6),
+                entropic: Color32::from_rgb(0x_FF, 0x_C1, 0x_07),
+                metropolis: Color32::from_rgb(0x_4D, 0x_B6, 0x_AC),
+                simple: Color32::from_gray(0x_E0),
+                reference: Color32::LIGHT_GRAY
+            },
+            Theme::Projector => Palette{
+                analytic: Color32::from_rgb(0x_C0, 0x_00, 0x_00),
+                wl: Color32::from_rgb(0x_00, 0x_00, 0x_D0),
+                entropic: Color32::from_rgb(0x_E0, 0x_70, 0x_00),
+                metropolis: Color32::from_rgb(0x_00, 0x_80, 0x_00),
+                simple: Color32::BLACK,
+                reference: Color32::from_gray(0x_40)
+            },
+            Theme::Colorblind => Palette{
+                analytic: Color32::from_rgb(0x_D5, 0x_5E, 0x_00),
+                wl: Color32::from_rgb(0x_00, 0x_72, 0x_B2),
+                entropic: Color32::from_rgb(0x_E6, 0x_9F, 0x_00),
+                metropolis: Color32::from_rgb(0x_00, 0x_9E, 0x_73),
+                simple: Color32::BLACK,
+                reference: Color32::from_rgb(0x_CC, 0x_79, 0x_A7)
+            }
+        }
+    }
+
+    /// Base width of the curves
+    pub fn linewidth(&self) -> f32
+    {
+        match self {
+            Theme::Light | Theme::Dark => 1.5,
+            Theme::Projector => 3.0,
+            Theme::Colorblind => 2.0
+        }
+    }
+}
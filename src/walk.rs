@@ -0,0 +1,233 @@
+use rand::Rng;
+use rand_pcg::Pcg64;
+use crate::{Model, ln_binomial, convolve};
+
+/// Largest number of steps for the area, which has N(N+1)/2+1 bins.
+/// This stays below `MAX_EXACT_BINS`, so the exact density of the area is always calculated
+pub const MAX_AREA_STEPS: usize = 200;
+
+/// What is measured of the random walk
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Observable{
+    /// Number of steps up, i.e., (end position + N) / 2
+    End,
+    /// Maximal position, including the start at 0
+    Max,
+    /// (area + N(N+1)/2) / 2, where the area is the sum of all positions after the start
+    Area
+}
+
+/// Flipping the direction of a step again undoes the move
+#[derive(Clone, Copy, Debug)]
+pub struct WalkMove{
+    pub index: usize
+}
+
+/// 1D random walk with N steps of ±1, starting at 0
+#[derive(Clone)]
+pub struct RandomWalk{
+    /// true for a step up
+    up: Vec<bool>,
+    observable: Observable,
+    energy: usize
+}
+
+impl RandomWalk{
+    pub fn new(n: usize, observable: Observable, rng: &mut Pcg64) -> Self
+    {
+        let up: Vec<bool> = (0..n)
+            .map(|_| rng.gen())
+            .collect();
+        let energy = measure(observable, up.iter().copied());
+        Self{
+            up,
+            observable,
+            energy
+        }
+    }
+
+    fn flip(&mut self, index: usize)
+    {
+        let step = &mut self.up[index];
+        *step = !*step;
+        // a step up at index i is part of the remaining N - i positions
+        let weight = match self.observable {
+            Observable::End => 1,
+            Observable::Area => self.up.len() - index,
+            Observable::Max => {
+                self.energy = measure(self.observable, self.up.iter().copied());
+                return;
+            }
+        };
+        if self.up[index] {
+            self.energy += weight;
+        } else {
+            self.energy -= weight;
+        }
+    }
+}
+
+/// Energy of the walk given by the directions of its steps
+fn measure<I>(observable: Observable, up: I) -> usize
+where I: ExactSizeIterator<Item = bool>
+{
+    let n = up.len();
+    match observable {
+        Observable::End => up.filter(|step| *step).count(),
+        Observable::Area => {
+            up.enumerate()
+                .filter(|(_, step)| *step)
+                .map(|(i, _)| n - i)
+                .sum()
+        },
+        Observable::Max => {
+            let mut pos: isize = 0;
+            let mut max = 0;
+            for step in up {
+                pos += if step { 1 } else { -1 };
+                max = max.max(pos);
+            }
+            max as usize
+        }
+    }
+}
+
+impl Model for RandomWalk{
+    type Move = WalkMove;
+
+    fn energy(&self) -> usize
+    {
+        self.energy
+    }
+
+    fn num_bins(&self) -> usize
+    {
+        let n = self.up.len();
+        match self.observable {
+            Observable::End | Observable::Max => n + 1,
+            Observable::Area => n * (n + 1) / 2 + 1
+        }
+    }
+
+    fn propose(&mut self, rng: &mut Pcg64) -> WalkMove
+    {
+        let index = rng.gen_range(0..self.up.len());
+        self.flip(index);
+        WalkMove { index }
+    }
+
+    fn undo(&mut self, m: &WalkMove)
+    {
+        self.flip(m.index);
+    }
+
+    fn exact_log_density(&self) -> Option<Vec<f64>>
+    {
+        let n = self.up.len();
+        match self.observable {
+            Observable::End => Some(ln_binomial(n)),
+            Observable::Max => {
+                // reflection principle: #(max = m) = #(end = m) + #(end = m + 1),
+                // only one of them has the right parity
+                let ends = ln_binomial(n);
+                let density = (0..=n)
+                    .map(|m| {
+                        let up = (n + m).div_ceil(2);
+                        ends[up]
+                    }).collect();
+                Some(density)
+            },
+            Observable::Area => {
+                // the step at index i contributes either 0 or N - i
+                let density = (1..=n)
+                    .fold(
+                        vec![0.0],
+                        |acc, weight| {
+                            let mut unit = vec![f64::NEG_INFINITY; weight + 1];
+                            unit[0] = 0.0;
+                            unit[weight] = 0.0;
+                            convolve(&acc, &unit)
+                        }
+                    );
+                Some(density)
+            }
+        }
+    }
+
+    fn sample_energy(&self, rng: &mut Pcg64) -> usize
+    {
+        let up = (0..self.up.len())
+            .map(|_| rng.gen::<bool>());
+        measure(self.observable, up)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use rand::SeedableRng;
+    use crate::{enumerate, transfer_matrix};
+
+    fn exact(n: usize, observable: Observable) -> Vec<f64>
+    {
+        let mut rng = Pcg64::seed_from_u64(1);
+        RandomWalk::new(n, observable, &mut rng)
+            .exact_log_density()
+            .unwrap()
+    }
+
+    fn assert_close(a: &[f64], b: &[f64])
+    {
+        assert_eq!(a.len(), b.len(), "{a:?} {b:?}");
+        for (x, y) in a.iter().zip(b) {
+            assert!(x == y || (x - y).abs() < 1e-9, "{a:?} {b:?}");
+        }
+    }
+
+    fn brute_force(n: usize, observable: Observable) -> Vec<f64>
+    {
+        let bins = match observable {
+            Observable::End | Observable::Max => n + 1,
+            Observable::Area => n * (n + 1) / 2 + 1
+        };
+        enumerate(n, 2, bins, |config| measure(observable, config.iter().map(|c| *c == 1)))
+            .unwrap()
+    }
+
+    #[test]
+    fn exact_matches_enumeration()
+    {
+        for n in [1, 4, 13] {
+            for observable in [Observable::End, Observable::Max, Observable::Area] {
+                assert_close(&exact(n, observable), &brute_force(n, observable));
+            }
+        }
+    }
+
+    #[test]
+    fn reflection_principle_matches_transfer_matrix()
+    {
+        // the state is the current position and the maximum so far
+        let n = 16;
+        let positions = 2 * n + 1;
+        let state = |pos: isize, max: usize| max * positions + (pos + n as isize) as usize;
+        let mut initial = vec![f64::NEG_INFINITY; (n + 1) * positions];
+        initial[state(0, 0)] = 0.0;
+        let density = transfer_matrix(
+            initial,
+            n,
+            |from, to| {
+                let pos = (from % positions) as isize - n as isize;
+                let max = from / positions;
+                [pos - 1, pos + 1].into_iter()
+                    .filter(|next| next.unsigned_abs() <= n)
+                    .any(|next| to == state(next, max.max(next.max(0) as usize)))
+                    .then_some(0.0)
+            }
+        );
+        let by_max: Vec<f64> = density.chunks(positions)
+            .map(|chunk| crate::ln_sum_exp(chunk.iter().copied()))
+            .collect();
+        assert_close(&exact(n, Observable::Max), &by_max);
+    }
+}
@@ -0,0 +1,569 @@
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
+use sampling::*;
+use crate::Model;
+
+/// Everything that happened during a single Markov step.
+/// Used to show the algorithm step by step
+#[derive(Clone, Debug)]
+pub struct StepInfo<Move>{
+    pub moves: Vec<Move>,
+    pub old_energy: usize,
+    pub new_energy: usize,
+    /// log g(old) before the update
+    pub log_g_old: f64,
+    /// log g(new) before the update
+    pub log_g_new: f64,
+    /// exp(log g(old) - log g(new)), the step is accepted if `random` is smaller
+    pub acceptance: f64,
+    pub random: f64,
+    pub accepted: bool,
+    /// bin the Markov chain is in after the step
+    pub bin: usize,
+    /// log g(bin) after the update
+    pub log_g_bin: f64,
+    /// hits of `bin` after the update, before a possible reset of the histogram
+    pub hits: usize,
+    /// `log_f` that was added to log g(bin), 0 for entropic sampling
+    pub log_f: f64,
+    /// `log_f` after the step, differs from `log_f` if it was refined
+    pub log_f_after: f64
+}
+
+struct Proposal{
+    old_energy: usize,
+    new_energy: usize,
+    acceptance: f64,
+    random: f64,
+    accepted: bool
+}
+
+/// Metropolis step with acceptance probability `exp(log g(old) - log g(new))`.
+/// Rejected moves are undone
+fn markov_step<M: Model>(
+    ensemble: &mut M,
+    rng: &mut Pcg64,
+    step_size: usize,
+    log_density: &[f64],
+    moves: &mut Vec<M::Move>
+) -> Proposal
+{
+    let old_energy = ensemble.energy();
+    ensemble.propose_steps(step_size, rng, moves);
+    let new_energy = ensemble.energy();
+
+    let acceptance = (log_density[old_energy] - log_density[new_energy]).exp();
+    let random: f64 = rng.gen();
+    let accepted = random < acceptance;
+    if !accepted {
+        ensemble.undo_steps(moves);
+    }
+    Proposal { old_energy, new_energy, acceptance, random, accepted }
+}
+
+/// When is the histogram of the Wang-Landau algorithm considered flat?
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Flatness{
+    /// Every bin was hit at least once since the last refinement
+    AllVisited,
+    /// The smallest bin has at least `ratio * mean` hits, e.g., the classic 0.8
+    MinOverMean(f64)
+}
+
+/// How `log_f` is reduced, once the histogram is flat
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Reduction{
+    /// `log_f` is divided by the factor, i.e., 2.0 corresponds to f -> sqrt(f)
+    Divide(f64),
+    /// Same as `Divide`, but as soon as `log_f` drops below `bins/t`
+    /// the 1/t algorithm is used, i.e., `log_f = bins/t` for each following step
+    OneOverT(f64)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WlParams{
+    /// `log_f` at the start of the simulation
+    pub log_f_start: f64,
+    /// The simulation is finished once `log_f` drops below the threshold
+    pub threshold: f64,
+    /// every `check_every` steps: check if the histogram is flat
+    pub check_every: usize,
+    pub flatness: Flatness,
+    pub reduction: Reduction,
+    /// How many moves are performed for each Markov step
+    pub step_size: usize
+}
+
+impl Default for WlParams{
+    fn default() -> Self {
+        Self {
+            log_f_start: 1.0,
+            threshold: 0.000001,
+            check_every: 100,
+            flatness: Flatness::AllVisited,
+            reduction: Reduction::OneOverT(2.0),
+            step_size: 1
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WangLandau<M: Model>{
+    ensemble: M,
+    rng: Pcg64,
+    params: WlParams,
+    log_density: Vec<f64>,
+    hist: HistUsizeFast,
+    log_f: f64,
+    step_count: usize,
+    one_over_t: bool,
+    moves: Vec<M::Move>
+}
+
+impl<M: Model> WangLandau<M>{
+    pub fn new(ensemble: M, rng: Pcg64, params: WlParams) -> Self
+    {
+        let bins = ensemble.num_bins();
+        let hist = HistUsizeFast::new_inclusive(0, bins - 1)
+            .unwrap();
+        Self{
+            ensemble,
+            rng,
+            params,
+            log_density: vec![0.0; bins],
+            hist,
+            log_f: params.log_f_start,
+            step_count: 0,
+            one_over_t: false,
+            moves: Vec::with_capacity(params.step_size)
+        }
+    }
+
+    pub fn log_f(&self) -> f64
+    {
+        self.log_f
+    }
+
+    pub fn is_finished(&self) -> bool
+    {
+        self.log_f < self.params.threshold
+    }
+
+    /// Is the 1/t algorithm currently used?
+    pub fn is_one_over_t(&self) -> bool
+    {
+        self.one_over_t
+    }
+
+    pub fn step_counter(&self) -> usize
+    {
+        self.step_count
+    }
+
+    pub fn params(&self) -> &WlParams
+    {
+        &self.params
+    }
+
+    /// Histogram since the last refinement of `log_f`
+    pub fn hist(&self) -> &HistUsizeFast
+    {
+        &self.hist
+    }
+
+    pub fn log_density_base10(&self) -> Vec<f64>
+    {
+        self.log_density
+            .iter()
+            .map(|val| *val * std::f64::consts::LOG10_E)
+            .collect()
+    }
+
+    pub fn wang_landau_while<F>(&mut self, mut cond: F)
+    where F: FnMut() -> bool
+    {
+        while !self.is_finished() && cond() {
+            self.wang_landau_step();
+        }
+    }
+
+    pub fn wang_landau_step(&mut self)
+    {
+        markov_step(
+            &mut self.ensemble,
+            &mut self.rng,
+            self.params.step_size,
+            &self.log_density,
+            &mut self.moves
+        );
+        self.update_after_step();
+    }
+
+    /// Same as `wang_landau_step`, but returns what happened
+    pub fn wang_landau_step_info(&mut self) -> StepInfo<M::Move>
+    {
+        let log_f = self.log_f;
+        let proposal = markov_step(
+            &mut self.ensemble,
+            &mut self.rng,
+            self.params.step_size,
+            &self.log_density,
+            &mut self.moves
+        );
+        let log_g_old = self.log_density[proposal.old_energy];
+        let log_g_new = self.log_density[proposal.new_energy];
+        let bin = self.ensemble.energy();
+        let hits = self.hist.hist()[bin] + 1;
+        self.update_after_step();
+        StepInfo{
+            moves: self.moves.clone(),
+            old_energy: proposal.old_energy,
+            new_energy: proposal.new_energy,
+            log_g_old,
+            log_g_new,
+            acceptance: proposal.acceptance,
+            random: proposal.random,
+            accepted: proposal.accepted,
+            bin,
+            log_g_bin: self.log_density[bin],
+            hits,
+            log_f,
+            log_f_after: self.log_f
+        }
+    }
+
+    fn update_after_step(&mut self)
+    {
+        self.step_count += 1;
+        let bin = self.ensemble.energy();
+        self.log_density[bin] += self.log_f;
+        self.hist.increment_quiet(bin);
+        self.adjust_log_f();
+    }
+
+    fn is_flat(&self) -> bool
+    {
+        let hist = self.hist.hist();
+        match self.params.flatness {
+            Flatness::AllVisited => hist.iter().all(|hits| *hits > 0),
+            Flatness::MinOverMean(ratio) => {
+                let min = *hist.iter().min().unwrap();
+                let sum: usize = hist.iter().sum();
+                let mean = sum as f64 / hist.len() as f64;
+                min > 0 && min as f64 >= ratio * mean
+            }
+        }
+    }
+
+    fn adjust_log_f(&mut self)
+    {
+        let bins_over_t = self.log_density.len() as f64 / self.step_count as f64;
+        if self.one_over_t {
+            self.log_f = bins_over_t;
+            return;
+        }
+        if !self.step_count.is_multiple_of(self.params.check_every) || !self.is_flat() {
+            return;
+        }
+        match self.params.reduction {
+            Reduction::Divide(factor) => {
+                self.log_f /= factor;
+            },
+            Reduction::OneOverT(factor) => {
+                self.log_f /= factor;
+                if self.log_f < bins_over_t {
+                    self.log_f = bins_over_t;
+                    self.one_over_t = true;
+                }
+            }
+        }
+        self.hist.reset();
+    }
+}
+
+/// When the entropic sampling takes over the estimate of the Wang-Landau simulation
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Handoff{
+    /// Start right away, i.e., from the flat prior
+    Immediately,
+    /// Start once `log_f` dropped below the value (or Wang-Landau finished)
+    LogF(f64),
+    /// Start once the Wang-Landau simulation finished
+    Finished,
+    /// Only start when requested by the user
+    Manual
+}
+
+/// When the estimate of the entropic sampling is refined
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RefineSchedule{
+    pub handoff: Handoff,
+    /// refine the estimate every `every` entropic steps
+    pub every: usize,
+    /// stop refining after this many refinements
+    pub rounds: usize
+}
+
+impl Default for RefineSchedule{
+    fn default() -> Self {
+        Self {
+            handoff: Handoff::Immediately,
+            every: 30000000,
+            rounds: 10
+        }
+    }
+}
+
+/// Entropic sampling, i.e., a Markov chain with the fixed weights of
+/// a Wang-Landau estimate
+#[derive(Clone)]
+pub struct Entropic<M: Model>{
+    ensemble: M,
+    rng: Pcg64,
+    step_size: usize,
+    log_density: Vec<f64>,
+    hist: HistUsizeFast,
+    step_count: usize,
+    schedule: RefineSchedule,
+    refinements: usize,
+    moves: Vec<M::Move>
+}
+
+impl<M: Model> Entropic<M>{
+    /// Start entropic sampling from the current state of the Wang-Landau simulation.
+    /// Its random numbers are seeded by the Wang-Landau simulation, so that both
+    /// do not use the same random stream
+    pub fn from_wl(wl: &mut WangLandau<M>, schedule: RefineSchedule) -> Self
+    {
+        Self{
+            ensemble: wl.ensemble.clone(),
+            rng: Pcg64::from_rng(&mut wl.rng).unwrap(),
+            step_size: wl.params.step_size,
+            log_density: wl.log_density.clone(),
+            hist: HistUsizeFast::new_inclusive(0, wl.log_density.len() - 1).unwrap(),
+            step_count: 0,
+            schedule,
+            refinements: 0,
+            moves: Vec::with_capacity(wl.params.step_size)
+        }
+    }
+
+    /// Changes when the estimate is refined. Refinements that already happened count
+    /// towards the new `rounds`
+    pub fn set_schedule(&mut self, schedule: RefineSchedule)
+    {
+        self.schedule = schedule;
+    }
+
+    /// How often the estimate was refined so far
+    pub fn refinements(&self) -> usize
+    {
+        self.refinements
+    }
+
+    pub fn step_counter(&self) -> usize
+    {
+        self.step_count
+    }
+
+    /// Histogram since the last refinement
+    pub fn hist(&self) -> &HistUsizeFast
+    {
+        &self.hist
+    }
+
+    /// Natural logarithm of the current estimate of the density of states
+    pub fn log_density_estimate(&self) -> &[f64]
+    {
+        &self.log_density
+    }
+
+    pub fn entropic_sampling_while<F>(&mut self, mut cond: F)
+    where F: FnMut() -> bool
+    {
+        while cond() {
+            self.entropic_step();
+        }
+    }
+
+    pub fn entropic_step(&mut self)
+    {
+        markov_step(
+            &mut self.ensemble,
+            &mut self.rng,
+            self.step_size,
+            &self.log_density,
+            &mut self.moves
+        );
+        self.update_after_step();
+    }
+
+    /// Same as `entropic_step`, but returns what happened
+    pub fn entropic_step_info(&mut self) -> StepInfo<M::Move>
+    {
+        let proposal = markov_step(
+            &mut self.ensemble,
+            &mut self.rng,
+            self.step_size,
+            &self.log_density,
+            &mut self.moves
+        );
+        let bin = self.ensemble.energy();
+        let hits = self.hist.hist()[bin] + 1;
+        let info = StepInfo{
+            moves: self.moves.clone(),
+            old_energy: proposal.old_energy,
+            new_energy: proposal.new_energy,
+            log_g_old: self.log_density[proposal.old_energy],
+            log_g_new: self.log_density[proposal.new_energy],
+            acceptance: proposal.acceptance,
+            random: proposal.random,
+            accepted: proposal.accepted,
+            bin,
+            log_g_bin: self.log_density[bin],
+            hits,
+            log_f: 0.0,
+            log_f_after: 0.0
+        };
+        self.update_after_step();
+        info
+    }
+
+    fn update_after_step(&mut self)
+    {
+        self.step_count += 1;
+        self.hist.increment_quiet(self.ensemble.energy());
+
+        if self.refinements < self.schedule.rounds 
+            && self.step_count.is_multiple_of(self.schedule.every)
+        {
+            self.refine_estimate();
+        }
+    }
+
+    /// Add the logarithm of the histogram to the estimate and reset the histogram.
+    /// Bins without hits are left unchanged
+    fn refine_estimate(&mut self)
+    {
+        self.refinements += 1;
+        self.log_density
+            .iter_mut()
+            .zip(self.hist.hist().iter())
+            .filter(|(_, hits)| **hits > 0)
+            .for_each(|(val, hits)| *val += (*hits as f64).ln());
+        self.hist.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use rand::SeedableRng;
+    use crate::CoinFlipSequence;
+
+    fn coins(n: usize, params: WlParams) -> WangLandau<CoinFlipSequence>
+    {
+        let mut rng = Pcg64::seed_from_u64(2398);
+        let ensemble = CoinFlipSequence::new(n, &mut rng);
+        WangLandau::new(ensemble, rng, params)
+    }
+
+    /// ln C(n, k) for all k
+    fn ln_binomial(n: usize) -> Vec<f64>
+    {
+        let ln_factorial = |m: usize| (1..=m).map(|i| (i as f64).ln()).sum::<f64>();
+        (0..=n)
+            .map(|k| ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k))
+            .collect()
+    }
+
+    #[test]
+    fn coins_reach_binomial()
+    {
+        let params = WlParams { threshold: 1e-5, ..Default::default() };
+        let mut wl = coins(10, params);
+        wl.wang_landau_while(|| true);
+        assert!(wl.is_finished());
+
+        // ln g is only known up to a constant
+        let exact = ln_binomial(10);
+        let offset = wl.log_density[0] - exact[0];
+        for (estimate, exact) in wl.log_density.iter().zip(exact) {
+            assert!((estimate - offset - exact).abs() < 0.1, "{:?}", wl.log_density);
+        }
+    }
+
+    #[test]
+    fn one_over_t_takes_over()
+    {
+        let params = WlParams { log_f_start: 0.1, ..Default::default() };
+        let mut wl = coins(10, params);
+        while !wl.is_one_over_t() {
+            assert!(wl.step_counter() < 1_000_000, "1/t was never reached");
+            wl.wang_landau_step();
+        }
+        let bins = wl.log_density.len() as f64;
+        assert_eq!(wl.log_f(), bins / wl.step_counter() as f64);
+        for _ in 0..100 {
+            wl.wang_landau_step();
+            assert_eq!(wl.log_f(), bins / wl.step_counter() as f64);
+        }
+    }
+
+    #[test]
+    fn min_over_mean_needs_every_bin()
+    {
+        let params = WlParams { flatness: Flatness::MinOverMean(0.8), ..Default::default() };
+        let mut wl = coins(4, params);
+        for bin in 1..5 {
+            for _ in 0..10 {
+                wl.hist.increment_quiet(bin);
+            }
+        }
+        assert!(!wl.is_flat());
+        for _ in 0..9 {
+            wl.hist.increment_quiet(0);
+        }
+        assert!(wl.is_flat());
+    }
+
+    #[test]
+    fn entropic_uses_own_random_numbers()
+    {
+        let mut wl = coins(50, WlParams::default());
+        let mut entropic = Entropic::from_wl(&mut wl, RefineSchedule::default());
+        let wl_moves: Vec<usize> = (0..20)
+            .map(|_| wl.wang_landau_step_info().moves[0].index)
+            .collect();
+        let entropic_moves: Vec<usize> = (0..20)
+            .map(|_| entropic.entropic_step_info().moves[0].index)
+            .collect();
+        assert_ne!(wl_moves, entropic_moves);
+    }
+
+    #[test]
+    fn refinement_adds_ln_hist()
+    {
+        let mut wl = coins(3, WlParams::default());
+        let mut entropic = Entropic::from_wl(&mut wl, RefineSchedule::default());
+        let before = entropic.log_density.clone();
+        let hits = [5, 0, 1, 20];
+        for (bin, count) in hits.iter().enumerate() {
+            for _ in 0..*count {
+                entropic.hist.increment_quiet(bin);
+            }
+        }
+        entropic.refine_estimate();
+
+        assert_eq!(entropic.refinements(), 1);
+        assert!(entropic.hist().hist().iter().all(|hits| *hits == 0));
+        for ((after, before), hits) in entropic.log_density.iter().zip(before).zip(hits) {
+            let expected = if hits > 0 {
+                before + (hits as f64).ln()
+            } else {
+                before
+            };
+            assert_eq!(*after, expected);
+        }
+    }
+}